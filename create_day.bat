@@ -1,5 +0,0 @@
-mkdir src\inputs\day_%1
-echo. > src\inputs\day_%1\input_example_1.txt
-echo. > src\inputs\day_%1\input.txt
-
-copy src\day_x.rs src\day_%1.rs
\ No newline at end of file
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+const HEADER: &str = "# Verified answers for each day, keyed by the input file they were solved from.
+# Add new ones with `AOC_2023 run <day> <part> --record`.
+";
+
+/// How an answer compares to the one recorded for it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Check {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_string = match self {
+            Self::Pass => "pass",
+            Self::Fail { .. } => "FAIL",
+            Self::Unknown => "unknown",
+        };
+        f.pad(display_string)
+    }
+}
+
+/// Answers stored per day, input file and part.
+///
+/// The file is a small subset of TOML, one table per day and input file:
+///
+/// ```toml
+/// [day_5."input_example_1.txt"]
+/// part_1 = 35
+/// part_2 = 46
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    entries: BTreeMap<(usize, String), [Option<String>; 2]>,
+}
+
+impl Answers {
+    /// Load the answers file. A missing file is treated as having no answers.
+    pub fn load(path: &str) -> Result<Self, String> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path).map_err(|e| format!("unable to read {}: {}", path, e))?;
+        Self::from_toml(&contents).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        let mut answers = Self::default();
+        let mut current_key: Option<(usize, String)> = None;
+
+        for (line_index, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.trim();
+            let line_error = |message: &str| format!("line {}: {}", line_index + 1, message);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                let header = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')).ok_or(line_error("unclosed table header"))?;
+                let (day_string, input_name) = header.split_once('.').ok_or(line_error("table must be [day_N.\"file\"]"))?;
+                let day = day_string.strip_prefix("day_").and_then(|x| x.parse::<usize>().ok()).ok_or(line_error("bad day in table name"))?;
+                current_key = Some((day, parse_value(input_name).ok_or(line_error("file name must be quoted"))?));
+                continue;
+            }
+
+            let (name, value) = line.split_once('=').ok_or(line_error("expected `part_N = answer`"))?;
+            let part = match name.trim() {
+                "part_1" => 1,
+                "part_2" => 2,
+                other => return Err(line_error(&format!("unknown key '{}'", other))),
+            };
+            let answer = parse_value(value.trim()).ok_or(line_error("bad answer value"))?;
+            let key = current_key.clone().ok_or(line_error("answer given before any table"))?;
+
+            answers.entries.entry(key).or_default()[part - 1] = Some(answer);
+        }
+
+        Ok(answers)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut contents = HEADER.to_string();
+        for ((day, input_name), parts) in &self.entries {
+            contents += &format!("\n[day_{}.\"{}\"]\n", day, input_name);
+            for (i, answer) in parts.iter().enumerate() {
+                if let Some(answer) = answer {
+                    contents += &format!("part_{} = {}\n", i + 1, format_value(answer));
+                }
+            }
+        }
+
+        contents
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_toml()).map_err(|e| format!("unable to write {}: {}", path, e))
+    }
+
+    pub fn get(&self, day: usize, part: usize, input_filename: &str) -> Option<&str> {
+        let parts = self.entries.get(&(day, input_name(input_filename)))?;
+        parts.get(part.checked_sub(1)?)?.as_deref()
+    }
+
+    /// Store an answer, returning the one it replaced if it was different.
+    pub fn record(&mut self, day: usize, part: usize, input_filename: &str, answer: &str) -> Option<String> {
+        let parts = self.entries.entry((day, input_name(input_filename))).or_default();
+        let previous = parts[part - 1].replace(answer.to_string());
+        previous.filter(|x| x != answer)
+    }
+
+    pub fn check(&self, day: usize, part: usize, input_filename: &str, answer: &str) -> Check {
+        match self.get(day, part, input_filename) {
+            Some(expected) if expected == answer => Check::Pass,
+            Some(expected) => Check::Fail { expected: expected.to_string() },
+            None => Check::Unknown,
+        }
+    }
+}
+
+/// Answers are keyed by file name so the same input can be read from anywhere.
+fn input_name(input_filename: &str) -> String {
+    Path::new(input_filename).file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or(input_filename.to_string())
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        return quoted.strip_suffix('"').map(|x| x.to_string());
+    }
+
+    value.parse::<i128>().ok().map(|x| x.to_string())
+}
+
+fn format_value(answer: &str) -> String {
+    match answer.parse::<i128>() {
+        Ok(_) => answer.to_string(),
+        Err(_) => format!("\"{}\"", answer),
+    }
+}
+
+/// Test helper comparing an answer with the answers file.
+///
+/// Answers that have not been recorded yet are reported as unknown rather than failing.
+pub fn assert_answer<T: Display>(day: usize, part: usize, input_filename: &str, answer: T) {
+    let answers = Answers::load(ANSWERS_FILE).expect("Answers file is invalid");
+    let answer = answer.to_string();
+
+    match answers.check(day, part, input_filename, &answer) {
+        Check::Pass => (),
+        Check::Fail { expected } => panic!("Day {} part {} for {}: got {}, expected {}", day, part, input_filename, answer, expected),
+        Check::Unknown => println!("Day {} part {} for {}: answer {} is unknown", day, part, input_filename, answer),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "# comment
+[day_5.\"input.txt\"]
+part_1 = 324724204
+part_2 = 104070862
+
+[day_12.\"input_example_1.txt\"]
+part_2 = \"abc\"
+";
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::from_toml(EXAMPLE).unwrap();
+        assert_eq!(answers.get(5, 1, "input.txt"), Some("324724204"));
+        assert_eq!(answers.get(5, 2, "src/inputs/day_5/input.txt"), Some("104070862"));
+        assert_eq!(answers.get(12, 1, "input_example_1.txt"), None);
+        assert_eq!(answers.get(12, 2, "input_example_1.txt"), Some("abc"));
+        assert_eq!(answers.get(6, 1, "input.txt"), None);
+    }
+
+    #[test]
+    fn bad_answers() {
+        assert!(Answers::from_toml("part_1 = 5").is_err());
+        assert!(Answers::from_toml("[day_x.\"input.txt\"]").is_err());
+        assert!(Answers::from_toml("[day_1.\"input.txt\"]\npart_3 = 5").is_err());
+        assert!(Answers::from_toml("[day_1.\"input.txt\"]\npart_1 = five").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers::from_toml(EXAMPLE).unwrap();
+        assert_eq!(answers.record(5, 1, "input.txt", "324724204"), None);
+        assert_eq!(answers.record(5, 1, "input.txt", "1"), Some("324724204".to_string()));
+        assert_eq!(answers.record(2, 2, "input.txt", "83105"), None);
+
+        let reloaded = Answers::from_toml(&answers.to_toml()).unwrap();
+        assert_eq!(reloaded, answers);
+        assert!(answers.to_toml().find("[day_2.").unwrap() < answers.to_toml().find("[day_5.").unwrap());
+    }
+
+    #[test]
+    fn check_answers() {
+        let answers = Answers::from_toml(EXAMPLE).unwrap();
+        assert_eq!(answers.check(5, 1, "input.txt", "324724204"), Check::Pass);
+        assert_eq!(answers.check(5, 1, "input.txt", "9284340"), Check::Fail { expected: "324724204".to_string() });
+        assert_eq!(answers.check(5, 1, "input_example_1.txt", "35"), Check::Unknown);
+    }
+
+    #[test]
+    fn answers_file_is_valid() {
+        assert!(Answers::load(ANSWERS_FILE).is_ok());
+    }
+}
@@ -0,0 +1,316 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+pub const YEAR: usize = 2023;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const CONFIG_FILE: &str = "aoc.toml";
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+const USER_AGENT: &str = "github.com/Kezahh/AOC_2023 input fetcher";
+
+/// Where to talk to and who to talk as.
+///
+/// Read from `aoc.toml` in the repo root, with the environment variables taking priority:
+///
+/// ```toml
+/// session = "53616c7465645f5f..."
+/// base_url = "https://adventofcode.com"
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Config {
+    pub session: String,
+    pub base_url: String,
+}
+
+impl Config {
+    pub fn load(config_path: &str) -> Result<Self, String> {
+        let mut session: Option<String> = None;
+        let mut base_url: Option<String> = None;
+
+        if Path::new(config_path).exists() {
+            let contents = fs::read_to_string(config_path).map_err(|e| format!("unable to read {}: {}", config_path, e))?;
+            (session, base_url) = Self::parse_config(&contents).map_err(|e| format!("{}: {}", config_path, e))?;
+        }
+
+        if let Ok(value) = env::var(SESSION_ENV) {
+            session = Some(value);
+        }
+        if let Ok(value) = env::var(BASE_URL_ENV) {
+            base_url = Some(value);
+        }
+
+        Ok(Self {
+            session: session.filter(|x| !x.is_empty())
+                .ok_or(format!("No session token. Set {} or add `session = \"...\"` to {}", SESSION_ENV, config_path))?,
+            base_url: base_url.unwrap_or(DEFAULT_BASE_URL.to_string()).trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn parse_config(contents: &str) -> Result<(Option<String>, Option<String>), String> {
+        let mut session: Option<String> = None;
+        let mut base_url: Option<String> = None;
+
+        for (line_index, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, value) = line.split_once('=').ok_or(format!("line {}: expected `key = \"value\"`", line_index + 1))?;
+            let value = value.trim().strip_prefix('"').and_then(|x| x.strip_suffix('"'))
+                .ok_or(format!("line {}: value must be quoted", line_index + 1))?.to_string();
+
+            match name.trim() {
+                "session" => session = Some(value),
+                "base_url" => base_url = Some(value),
+                other => return Err(format!("line {}: unknown key '{}'", line_index + 1, other)),
+            }
+        }
+
+        Ok((session, base_url))
+    }
+
+    fn day_url(&self, day: usize) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    fn get(&self, url: &str) -> Result<String, String> {
+        let response = ureq::get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|e| format!("request failed: {}", e))?;
+
+        response.into_string().map_err(|e| format!("unable to read response from {}: {}", url, e))
+    }
+
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let response = ureq::post(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(form)
+            .map_err(|e| format!("request failed: {}", e))?;
+
+        response.into_string().map_err(|e| format!("unable to read response from {}: {}", url, e))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FetchOutcome {
+    Downloaded,
+    Cached,
+}
+
+/// Download a day's input to `input_filename`, unless it has already been downloaded.
+pub fn fetch_input(config: &Config, day: usize, input_filename: &str) -> Result<FetchOutcome, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("There is no day {}", day));
+    }
+
+    let input_path = Path::new(input_filename);
+    if input_path.is_file() && input_path.metadata().map(|x| x.len() > 0).unwrap_or(false) {
+        return Ok(FetchOutcome::Cached);
+    }
+
+    let input_data = config.get(&(config.day_url(day) + "/input"))?;
+
+    if let Some(folder) = input_path.parent() {
+        fs::create_dir_all(folder).map_err(|e| format!("unable to create {}: {}", folder.display(), e))?;
+    }
+    fs::write(input_path, input_data).map_err(|e| format!("unable to write {}: {}", input_filename, e))?;
+
+    Ok(FetchOutcome::Downloaded)
+}
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SubmitOutcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited,
+    AlreadySolved,
+    Unrecognised,
+}
+
+impl SubmitOutcome {
+    pub fn from_response(response: &str) -> Self {
+        if response.contains("That's the right answer") {
+            Self::Correct
+        } else if response.contains("That's not the right answer") {
+            if response.contains("your answer is too high") {
+                Self::TooHigh
+            } else if response.contains("your answer is too low") {
+                Self::TooLow
+            } else {
+                Self::Wrong
+            }
+        } else if response.contains("You gave an answer too recently") {
+            Self::RateLimited
+        } else if response.contains("You don't seem to be solving the right level") {
+            Self::AlreadySolved
+        } else {
+            Self::Unrecognised
+        }
+    }
+
+    /// Whether the outcome says anything about the answer itself.
+    pub fn is_verdict(&self) -> bool {
+        matches!(self, Self::Correct | Self::TooHigh | Self::TooLow | Self::Wrong)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::TooHigh => "too_high",
+            Self::TooLow => "too_low",
+            Self::Wrong => "wrong",
+            Self::RateLimited => "rate_limited",
+            Self::AlreadySolved => "already_solved",
+            Self::Unrecognised => "unrecognised",
+        }
+    }
+
+    pub fn from_name(input_string: &str) -> Option<Self> {
+        [Self::Correct, Self::TooHigh, Self::TooLow, Self::Wrong, Self::RateLimited, Self::AlreadySolved, Self::Unrecognised]
+            .into_iter()
+            .find(|x| x.name() == input_string)
+    }
+}
+
+/// Post an answer for one part of a day.
+pub fn submit_answer(config: &Config, day: usize, part: usize, answer: &str) -> Result<SubmitOutcome, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("There is no day {}", day));
+    }
+    if part != 1 && part != 2 {
+        return Err(format!("Part must be 1 or 2, not {}", part));
+    }
+
+    let level = part.to_string();
+    let response = config.post_form(&(config.day_url(day) + "/answer"), &[("level", &level), ("answer", answer)])?;
+
+    Ok(SubmitOutcome::from_response(&response))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A stub server that answers `requests` requests with the given status and body,
+    /// handing back the request lines and headers it received.
+    fn stub_server(requests: usize, status: &'static str, body: &'static str) -> (String, JoinHandle<Vec<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut all_requests: Vec<Vec<String>> = Vec::new();
+            for _ in 0..requests {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request: Vec<String> = Vec::new();
+                let mut content_length: usize = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_string();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length: ") {
+                        content_length = length.parse().unwrap();
+                    }
+                    request.push(line);
+                }
+                let mut request_body = vec![0; content_length];
+                std::io::Read::read_exact(&mut reader, &mut request_body).unwrap();
+                request.push(String::from_utf8(request_body).unwrap());
+
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+                all_requests.push(request);
+            }
+            all_requests
+        });
+
+        (base_url, handle)
+    }
+
+    fn temp_input(name: &str) -> String {
+        let folder = env::temp_dir().join(format!("aoc_2023_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        folder.join("day_3").join("input.txt").to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn parse_config() {
+        let contents = "# my token\nsession = \"abc123\"\nbase_url = \"http://localhost:8080\"\n";
+        assert_eq!(Config::parse_config(contents), Ok((Some("abc123".to_string()), Some("http://localhost:8080".to_string()))));
+        assert!(Config::parse_config("session = abc123").is_err());
+        assert!(Config::parse_config("token = \"abc123\"").is_err());
+    }
+
+    #[test]
+    fn fetch_and_cache() {
+        let (base_url, handle) = stub_server(1, "200 OK", "467..114..\n...*......\n");
+        let config = Config { session: "abc123".to_string(), base_url };
+        let input_filename = temp_input("fetch");
+
+        assert_eq!(fetch_input(&config, 3, &input_filename), Ok(FetchOutcome::Downloaded));
+        assert_eq!(fs::read_to_string(&input_filename).unwrap(), "467..114..\n...*......\n");
+
+        // The stub only answers once, so this must come from the file.
+        assert_eq!(fetch_input(&config, 3, &input_filename), Ok(FetchOutcome::Cached));
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[0][0], "GET /2023/day/3/input HTTP/1.1");
+        assert!(requests[0].contains(&"Cookie: session=abc123".to_string()));
+    }
+
+    #[test]
+    fn fetch_errors() {
+        let (base_url, handle) = stub_server(1, "400 Bad Request", "Please log in");
+        let config = Config { session: "expired".to_string(), base_url };
+        let input_filename = temp_input("fetch_errors");
+
+        assert!(fetch_input(&config, 3, &input_filename).is_err());
+        assert!(!Path::new(&input_filename).exists());
+        assert!(fetch_input(&config, 26, &input_filename).is_err());
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn submit_outcomes() {
+        let wrong = "<article><p>That's not the right answer; your answer is too low. If you're stuck...</p></article>";
+        assert_eq!(SubmitOutcome::from_response(wrong), SubmitOutcome::TooLow);
+        assert_eq!(SubmitOutcome::from_response(&wrong.replace("too low", "too high")), SubmitOutcome::TooHigh);
+        assert_eq!(SubmitOutcome::from_response("<p>That's not the right answer. If you're stuck...</p>"), SubmitOutcome::Wrong);
+        assert_eq!(SubmitOutcome::from_response("<p>That's the right answer! You are one gold star closer</p>"), SubmitOutcome::Correct);
+        assert_eq!(SubmitOutcome::from_response("<p>You gave an answer too recently; You have 38s left to wait.</p>"), SubmitOutcome::RateLimited);
+        assert_eq!(SubmitOutcome::from_response("<p>You don't seem to be solving the right level.</p>"), SubmitOutcome::AlreadySolved);
+        assert_eq!(SubmitOutcome::from_response("<p>Something else</p>"), SubmitOutcome::Unrecognised);
+
+        assert_eq!(SubmitOutcome::from_name("too_low"), Some(SubmitOutcome::TooLow));
+        assert_eq!(SubmitOutcome::from_name("nope"), None);
+    }
+
+    #[test]
+    fn submit() {
+        let (base_url, handle) = stub_server(1, "200 OK", "<p>That's not the right answer; your answer is too low.</p>");
+        let config = Config { session: "abc123".to_string(), base_url };
+
+        assert_eq!(submit_answer(&config, 5, 2, "9284340"), Ok(SubmitOutcome::TooLow));
+        assert!(submit_answer(&config, 5, 3, "9284340").is_err());
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[0][0], "POST /2023/day/5/answer HTTP/1.1");
+        assert!(requests[0].contains(&"Cookie: session=abc123".to_string()));
+        assert_eq!(requests[0].last().unwrap(), "level=2&answer=9284340");
+    }
+}
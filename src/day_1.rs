@@ -1,5 +1,7 @@
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::Solution;
+use crate::trace;
 
 #[derive(Clone)]
 struct NumberWord {
@@ -8,7 +10,8 @@ struct NumberWord {
     index: usize,
 }
 
-fn solve_part_one(input_lines: Vec<String>) -> usize {
+/// Sum of the first and last digit on each line.
+pub fn sum_calibration_values(input_lines: Vec<String>) -> usize {
     let mut input_numbers: Vec<Vec<usize>> = Vec::new();
 
     for line in input_lines {
@@ -18,7 +21,7 @@ fn solve_part_one(input_lines: Vec<String>) -> usize {
                 current_numbers.push((c as usize) - 48);  //48 is ascii for '0'
             }
         }
-        //println!("{:?}", current_numbers);
+        trace!("{:?}", current_numbers);
         input_numbers.push(current_numbers);
     }
 
@@ -36,7 +39,6 @@ fn _solve_part_two(mut input_lines: Vec<String>) -> usize {
     
     for i in 0..input_lines.len() {
         let mut finished = false;
-        print!("{:?}", input_lines[i]);
 
         while !finished {
             let mut indexes: Vec<NumberWord> = Vec::new();
@@ -46,8 +48,6 @@ fn _solve_part_two(mut input_lines: Vec<String>) -> usize {
                     indexes.push(NumberWord{number: number_words[j].to_string(), number_real: j, index: index.unwrap()});
                 }
             }
-            //println!("{:?}", input_lines[i]);
-            //println!("{:?}", indexes);
             if indexes.len() > 0 {
                 indexes.sort_by(|a, b| a.index.cmp(&b.index));
 
@@ -59,12 +59,10 @@ fn _solve_part_two(mut input_lines: Vec<String>) -> usize {
                 finished = true;
             }
         }
-        println!(" --> {:?}", input_lines[i]);
+        trace!("{:?} --> {:?}", input_lines_original[i], input_lines[i]);
         //input_lines[i] = input_lines[i].replace(&number_word.number, number_word.number_real.to_string().as_str());
         
         //input_lines[i] = input_lines[i].replace(number_words[j], j.to_string().as_str());
-
-        //println!("{:?}", indexes);
     }
 
 
@@ -77,9 +75,7 @@ fn _solve_part_two(mut input_lines: Vec<String>) -> usize {
                 current_numbers.push((c as usize) - 48);  //48 is ascii for '0'
             }
         }
-        //println!("{:?}", current_numbers);
-
-        println!("{:?} --> {:?} --> {:?}, {:?}", input_lines_original[i], input_lines[i], &current_numbers[0], &current_numbers[current_numbers.len() - 1]);
+        trace!("{:?} --> {:?} --> {:?}, {:?}", input_lines_original[i], input_lines[i], &current_numbers[0], &current_numbers[current_numbers.len() - 1]);
         input_numbers.push(current_numbers);
 
         
@@ -93,7 +89,8 @@ fn _solve_part_two(mut input_lines: Vec<String>) -> usize {
     return answer_numbers.iter().sum();
 }
 
-fn solve_part_twob(mut input_lines: Vec<String>) -> usize {
+/// Like `sum_calibration_values`, but digits spelled out as words also count.
+pub fn sum_calibration_values_with_words(mut input_lines: Vec<String>) -> usize {
     let input_lines_original = input_lines.clone();
     let number_words: Vec<Vec<&str>> = vec![
         vec!["zero", "0o"],
@@ -109,12 +106,9 @@ fn solve_part_twob(mut input_lines: Vec<String>) -> usize {
     ];
     
     for i in 0..input_lines.len() {
-        print!("{:?}", input_lines[i]);
-
         for number_word in &number_words {
             input_lines[i] = input_lines[i].replace(number_word[0], number_word[1]);
         }
-        println!("{:?}", input_lines[i]);
     }
 
 
@@ -127,9 +121,7 @@ fn solve_part_twob(mut input_lines: Vec<String>) -> usize {
                 current_numbers.push((c as usize) - 48);  //48 is ascii for '0'
             }
         }
-        //println!("{:?}", current_numbers);
-
-        println!("{:?} --> {:?} --> {:?}, {:?}", input_lines_original[i], input_lines[i], &current_numbers[0], &current_numbers[current_numbers.len() - 1]);
+        trace!("{:?} --> {:?} --> {:?}, {:?}", input_lines_original[i], input_lines[i], &current_numbers[0], &current_numbers[current_numbers.len() - 1]);
         input_numbers.push(current_numbers);
 
         
@@ -143,38 +135,59 @@ fn solve_part_twob(mut input_lines: Vec<String>) -> usize {
     return answer_numbers.iter().sum();
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<String>;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        Ok(input_lines)
+    }
+
+    fn part_1(parsed: &Self::Parsed) -> Self::Answer {
+        sum_calibration_values(parsed.clone())
+    }
+
+    fn part_2(parsed: &Self::Parsed) -> Self::Answer {
+        sum_calibration_values_with_words(parsed.clone())
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn example_1() {
-        let input_lines = generic::read_in_file("src/inputs/day_1/input_example_1.txt");
-        assert!(solve_part_one(input_lines) == 142);
+        let input_filename = "src/inputs/day_1/input_example_1.txt";
+        let answer = generic::solve::<Day1>(input_filename, false);
+        answers::assert_answer(1, 1, input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let input_lines = generic::read_in_file("src/inputs/day_1/input.txt");
-        let answer = solve_part_one(input_lines);
+        let input_filename = "src/inputs/day_1/input.txt";
+        let answer = generic::solve::<Day1>(input_filename, false);
         println!("Answer = {}", answer);
-        assert!(answer == 54331);
+        answers::assert_answer(1, 1, input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let input_lines = generic::read_in_file("src/inputs/day_1/input_example_2.txt");
-        let answer = solve_part_twob(input_lines);
+        let input_filename = "src/inputs/day_1/input_example_2.txt";
+        let answer = generic::solve::<Day1>(input_filename, true);
         println!("Answer = {}", answer);
-        assert!(answer == 281);
+        answers::assert_answer(1, 2, input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let input_lines = generic::read_in_file("src/inputs/day_1/input.txt");
-        let answer = solve_part_twob(input_lines);
+        let input_filename = "src/inputs/day_1/input.txt";
+        let answer = generic::solve::<Day1>(input_filename, true);
         println!("Answer = {}", answer);
-        assert!(answer == 54518);
+        answers::assert_answer(1, 2, input_filename, answer);
     }
 }
\ No newline at end of file
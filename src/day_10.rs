@@ -1,7 +1,11 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_10";
 
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::direction::{Direction, Position};
+use crate::generic::{Grid, Solution};
+use crate::{debug, trace};
+use std::fmt::Display;
 
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -12,14 +16,15 @@ enum TileType {
     None,
 }
 
-impl TileType {
-    fn as_char(&self) -> char {
-        match self {
+impl Display for TileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_char = match self {
             Self::Pipe => 'P',
             Self::Inside => 'I',
             Self::Outside => 'O',
             Self::None => '.',
-        }
+        };
+        write!(f, "{}", display_char)
     }
 }
 
@@ -42,34 +47,9 @@ impl TilePipeCount {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct Position {
-    row: usize,
-    col: usize,
-}
-
-
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn opposite(&self) -> Self {
-        match self {
-            Self::Up => Self::Down,
-            Self::Down => Self::Up,
-            Self::Left => Self::Right,
-            Self::Right => Self::Left,
-        }
-    }
-}
 
 #[derive(Debug, Eq, PartialEq, Clone, Ord, PartialOrd)]
-enum PipeType {
+pub enum PipeType {
     StartPosition,
     Vertical,
     Horizontal,
@@ -124,6 +104,7 @@ impl PipeType {
                 Self::SouthToEast => true,
                 _ => false,
             }
+            _ => false,
         }
     }
 
@@ -133,57 +114,39 @@ impl PipeType {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Pipe {
-    pipe_type: PipeType,
-    position: Position,
+pub struct Pipe {
+    pub pipe_type: PipeType,
+    pub position: Position,
 }
 
-struct PipeMap {
-    actual_map: Vec<Vec<Pipe>>,
+pub struct PipeMap {
+    pub actual_map: Grid<Pipe>,
 }
 
 impl PipeMap {
-    fn from_input_lines(input_lines: &Vec<String>) -> Self {
-        let mut map: Vec<Vec<Pipe>> = Vec::new();
-        
-        for row in 0..input_lines.len() {
-            let mut pipe_row: Vec<Pipe> = Vec::new();
-            for col in 0..input_lines[row].len() {
-                pipe_row.push(Pipe{
-                    pipe_type: PipeType::from_char(input_lines[row].chars().nth(col).expect("Not a char")),
-                    position: Position { row: row, col: col },
-                })
-            }
-            map.push(pipe_row);
-        }
-
-        return Self {
-            actual_map: map,
-        };
+    pub fn from_input_lines(input_lines: &[String]) -> Result<Self, AocError> {
+        let pipe_types: Grid<PipeType> = Grid::parse(input_lines, |x| Ok(PipeType::from_char(x)))?;
+        let map = Grid::from_fn(pipe_types.rows(), pipe_types.cols(), |row, col| Pipe {
+            pipe_type: pipe_types[(row, col)].clone(),
+            position: Position { row, col },
+        });
+
+        Ok(Self { actual_map: map })
     }
 
-    fn get_pipe(&self, row: usize, col: usize) -> &Pipe {
-        return &self.actual_map[row][col];
+    pub fn get_pipe(&self, row: usize, col: usize) -> &Pipe {
+        &self.actual_map[(row, col)]
     }
 
-    fn get_start_pipe(&self) -> Option<&Pipe> {
-        for pipe_row in self.actual_map.iter() {
-            for pipe in pipe_row {
-                if pipe.pipe_type == PipeType::StartPosition {
-                    return Some(&pipe);
-                }
-            }
-        }
-    
-        return None;
+    pub fn get_start_pipe(&self) -> Option<&Pipe> {
+        self.actual_map.iter().find(|x| x.pipe_type == PipeType::StartPosition)
     }
 
     fn get_start_pipe_type(&self) -> PipeType {
         let start_pipe = self.get_start_pipe().unwrap();
 
-        let all_directions = vec![Direction::Up, Direction::Down, Direction::Left, Direction::Right];
         let mut neighbour_directions: Vec<Direction> = Vec::new();
-        for d in all_directions {
+        for d in Direction::CARDINALS {
             if start_pipe.pipe_type.connects_direction(&d) {
                 let temp_neighbour = self.get_neighbour_pipe_direction(start_pipe, &d);
                 if !temp_neighbour.is_none() {
@@ -193,8 +156,8 @@ impl PipeMap {
                 }
             }
         }
-        let neighbour_directions_tuple = (neighbour_directions[0].clone(), neighbour_directions[1].clone());
-        //println!("\tNeighbour directions = {:?}", neighbour_directions);
+        let neighbour_directions_tuple = (neighbour_directions[0], neighbour_directions[1]);
+        trace!("Start pipe connects {:?}", neighbour_directions);
         
         return match neighbour_directions_tuple {
             (Direction::Up, Direction::Down) | (Direction::Down, Direction::Up) => PipeType::Vertical,
@@ -208,41 +171,16 @@ impl PipeMap {
     }
 
     fn get_neighbour_pipe_direction(&self, target_pipe: &Pipe, direction: &Direction) -> Option<&Pipe> {
-        match direction {
-            Direction::Up =>
-                if target_pipe.position.row > 0 {
-                    return Some(&self.actual_map[target_pipe.position.row - 1][target_pipe.position.col]);
-                },
-            Direction::Down =>
-                if target_pipe.position.row < (self.actual_map.len() - 1) {
-                    return Some(&self.actual_map[target_pipe.position.row + 1][target_pipe.position.col]);
-                },
-            Direction::Left =>
-                if target_pipe.position.col > 0 {
-                    return Some(&self.actual_map[target_pipe.position.row][target_pipe.position.col - 1]);
-                },
-            Direction::Right =>
-                if target_pipe.position.col < (self.actual_map[0].len() - 1) {
-                    return Some(&self.actual_map[target_pipe.position.row][target_pipe.position.col + 1]);
-                },
-        }
-
-        return None
+        self.actual_map.step(target_pipe.position, *direction).map(|x| &self.actual_map[x])
     }
 
     fn get_neighbour_pipes(&self, target_pipe: &Pipe) -> Vec<&Pipe> {
         let mut neighbour_pipes: Vec<&Pipe> = Vec::new();
-        let directions: Vec<Direction> = vec![Direction::Up, Direction::Down, Direction::Left, Direction::Right];
-
-        //println!("Get neighbours of: {:?}", target_pipe);
-        for d in directions {
+        for d in Direction::CARDINALS {
             if target_pipe.pipe_type.connects_direction(&d) {
-                //println!("\t connects to {:?}", d);
                 let neighbour_pipe: Option<&Pipe> = self.get_neighbour_pipe_direction(&target_pipe, &d);
                 if !neighbour_pipe.is_none() {
-                    //println!("\t\tfound neighbour {:?}", neighbour_pipe);
                     if neighbour_pipe.unwrap().pipe_type.connects_direction(&d.opposite()) {
-                        //println!("\t\t\tneighbour connects!");
                         neighbour_pipes.push(neighbour_pipe.unwrap());
                     }
                 }
@@ -251,72 +189,80 @@ impl PipeMap {
 
         return neighbour_pipes;
     }
-}
 
+    pub fn get_pipe_loop(&self) -> Vec<&Pipe> {
+        let start_position: &Pipe = self.get_start_pipe().expect("No start position found");
 
+        let neighbours: Vec<&Pipe> = self.get_neighbour_pipes(&start_position);
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let map: PipeMap = PipeMap::from_input_lines(&input_lines);
-    let start_position: &Pipe = map.get_start_pipe().expect("No start position found");
+        let mut all_pipe_parts: Vec<&Pipe> = Vec::new();
 
-    let neighbours: Vec<&Pipe> = map.get_neighbour_pipes(&start_position);
-    let mut all_steps: Vec<usize> = Vec::new();
+        let mut finished = false;
+        let mut new_neighbours;
+        let mut steps = 1;
+        let mut previous: &Pipe = start_position;
+        let mut current_neighbour: &Pipe = neighbours[0];
 
-    let mut all_pipe_parts: Vec<&Pipe> = Vec::new();
-    
-    //println!("{:?}", neighbour);
-    let mut finished = false;
-    let mut new_neighbours;
-    let mut steps = 1;
-    let mut previous: &Pipe = start_position;
-    let mut current_neighbour: &Pipe = neighbours[0];
+        all_pipe_parts.push(start_position);
 
-    all_pipe_parts.push(start_position);
-
-    println!("STARTING NOW");
-    while !finished {
-        all_pipe_parts.push(current_neighbour);
-        //println!("getting neighbours of {:?}", current_neighbour);
-        //println!("(previous is {:?}", previous);
-        new_neighbours = map.get_neighbour_pipes(current_neighbour);
-        //println!("\t{:?}", new_neighbours);
-        if new_neighbours.len() == 1 || (new_neighbours.contains(&start_position) && steps != 1) {
-            finished = true;
-        }
+        while !finished {
+            all_pipe_parts.push(current_neighbour);
+            new_neighbours = self.get_neighbour_pipes(current_neighbour);
+            trace!("Step {}: {:?} (previous {:?}) connects to {:?}", steps, current_neighbour.position, previous.position, new_neighbours);
+            if new_neighbours.len() == 1 || (new_neighbours.contains(&start_position) && steps != 1) {
+                finished = true;
+            }
 
-        // increment
-        for n in new_neighbours {
-            if n != previous {
-                previous = current_neighbour;
-                current_neighbour = n;
-                break;
-                //println!("\t(Next Step. previous = {:?}, current = {:?})", previous, current_neighbour);
+            // increment
+            for n in new_neighbours {
+                if n != previous {
+                    previous = current_neighbour;
+                    current_neighbour = n;
+                    break;
+                }
             }
+
+            steps += 1;
         }
 
-        steps += 1;
+        all_pipe_parts
     }
-    all_steps.push(steps);
+}
+
+
 
-    if part_2 {
-        let mut tile_map: Vec<Vec<TileType>> = vec![vec![TileType::None; map.actual_map[0].len()]; map.actual_map.len()];
-        let mut pipe_count_map: Vec<Vec<TilePipeCount>> = vec![vec![TilePipeCount::blank(); map.actual_map[0].len()]; map.actual_map.len()];
+pub struct Day10;
 
-        for pipe in all_pipe_parts {
-            tile_map[pipe.position.row][pipe.position.col] = TileType::Pipe;
+impl Solution for Day10 {
+    type Parsed = PipeMap;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        PipeMap::from_input_lines(&input_lines)
+    }
+
+    fn part_1(map: &Self::Parsed) -> Self::Answer {
+        map.get_pipe_loop().len() / 2
+    }
+
+    fn part_2(map: &Self::Parsed) -> Self::Answer {
+        let mut tile_map: Grid<TileType> = Grid::new(map.actual_map.rows(), map.actual_map.cols(), TileType::None);
+        let mut pipe_count_map: Grid<TilePipeCount> = Grid::new(map.actual_map.rows(), map.actual_map.cols(), TilePipeCount::blank());
+
+        for pipe in map.get_pipe_loop() {
+            tile_map[pipe.position] = TileType::Pipe;
         }
 
         // Get above and below
-        for col in 0..tile_map[0].len() {
+        for col in 0..tile_map.cols() {
             let mut current_above_count: usize = 0;
             let mut bend_to_east: bool = false;
             let mut bend_to_west: bool = false;
 
-            for row in 0..tile_map.len() {
-                pipe_count_map[row][col].up = current_above_count;
-                
-                if tile_map[row][col] == TileType::Pipe {
+            for row in 0..tile_map.rows() {
+                pipe_count_map[(row, col)].up = current_above_count;
+            
+                if tile_map[(row, col)] == TileType::Pipe {
                     let mut pipe_type: PipeType = map.get_pipe(row, col).pipe_type.clone();
                     if pipe_type == PipeType::StartPosition {
                         pipe_type = map.get_start_pipe_type();
@@ -355,21 +301,21 @@ fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
                 }
             }
 
-            for row in (0..tile_map.len()).rev() {
-                pipe_count_map[row][col].down = current_above_count - pipe_count_map[row][col].up;
+            for row in (0..tile_map.rows()).rev() {
+                pipe_count_map[(row, col)].down = current_above_count - pipe_count_map[(row, col)].up;
             }
         }
 
         // Get left and right
-        for row in 0..tile_map.len() {
+        for row in 0..tile_map.rows() {
             let mut current_left_count: usize = 0;
             let mut bend_to_north: bool = false;
             let mut bend_to_south: bool = false;
 
-            for col in 0..tile_map[0].len() {
-                pipe_count_map[row][col].left = current_left_count;
+            for col in 0..tile_map.cols() {
+                pipe_count_map[(row, col)].left = current_left_count;
 
-                if tile_map[row][col] == TileType::Pipe {
+                if tile_map[(row, col)] == TileType::Pipe {
                     let mut pipe_type: PipeType = map.get_pipe(row, col).pipe_type.clone();
                     if pipe_type == PipeType::StartPosition {
                         pipe_type = map.get_start_pipe_type();
@@ -408,59 +354,30 @@ fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
                 }
             }
 
-            let _all_left_side: Vec<usize> = pipe_count_map[row].iter().map(|x| x.left).collect::<Vec<usize>>();
-            //println!("Beginning cols for row {}", row);
-            //println!("\tcurrent_left_count = {}", current_left_count);
-            //println!("\t{:?}", _all_left_side);
+            trace!("Row {} crosses {} vertical pipes", row, current_left_count);
 
-            for col in (0..tile_map[0].len()).rev() {
-                //println!("\t\tSetting right for {} = {}", col, (current_left_count - pipe_count_map[row][col].left));
-                pipe_count_map[row][col].right = current_left_count - pipe_count_map[row][col].left;
+            for col in (0..tile_map.cols()).rev() {
+                pipe_count_map[(row, col)].right = current_left_count - pipe_count_map[(row, col)].left;
             }
         }
 
 
-        for row in 0..tile_map.len() {
-            for col in 0..tile_map[0].len() {
-                if tile_map[row][col] != TileType::Pipe {
-                    let pc = &pipe_count_map[row][col];
+        for row in 0..tile_map.rows() {
+            for col in 0..tile_map.cols() {
+                if tile_map[(row, col)] != TileType::Pipe {
+                    let pc = &pipe_count_map[(row, col)];
                     if pc.up % 2 == 1 && pc.down % 2 == 1 && pc.left % 2 == 1 && pc.right %2 == 1 {
-                        tile_map[row][col] = TileType::Inside;
+                        tile_map[(row, col)] = TileType::Inside;
                     } else {
-                        tile_map[row][col] = TileType::Outside;
+                        tile_map[(row, col)] = TileType::Outside;
                     }
                 }
             }
         }
 
-        let mut count_inside = 0;
-        for row in 0..tile_map.len() {
-            let mut row_string = String::new();
-            // let mut other_row_string = String::new();
-            for col in 0..tile_map[0].len() {
-                row_string.push(tile_map[row][col].as_char());
-                if tile_map[row][col] == TileType::Inside {
-                    count_inside += 1;
-                }
-                // if tile_map[row][col] == TileType::Pipe {
-                //     //row_string.push('P');
-                //     row_string += format!("{}", pipe_count_map[row][col].left).as_str();
-                //     other_row_string += format!("{}", pipe_count_map[row][col].right).as_str();
-                // } else {
-                //     row_string += format!("{}", pipe_count_map[row][col].left).as_str();
-                //     other_row_string += format!("{}", pipe_count_map[row][col].right).as_str();
-                // }
-            }
-            println!("{:?}", row_string);
-            //println!("{:?}\t\t{:?}", row_string, other_row_string);
-        }
-
-        //println!("{:?}", map.get_pipe(6, 14));
-        //println!("\t{:?}", pipe_count_map[6][14]);
+        debug!("Tiles:\n{}", tile_map);
 
-        return count_inside;
-    } else {
-        return all_steps.iter().max().expect("Steps vector is empty") / 2;
+        tile_map.iter().filter(|x| **x == TileType::Inside).count()
     }
 }
 
@@ -468,12 +385,13 @@ fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn test_pipes() {
         // Do a quick test here
-        let input_lines: Vec<String> = generic::read_in_file((INPUTS_FOLDER.to_owned() + "/input_example_1.txt").as_str());
-        let map: PipeMap = PipeMap::from_input_lines(&input_lines);
+        let input_lines: Vec<String> = generic::read_in_file((INPUTS_FOLDER.to_owned() + "/input_example_1.txt").as_str()).unwrap();
+        let map: PipeMap = PipeMap::from_input_lines(&input_lines).unwrap();
 
         assert!(map.get_pipe(1, 1).pipe_type.connects(&map.get_pipe(1, 2).pipe_type, &Direction::Right));
         assert!(map.get_pipe(1, 2).pipe_type.connects(&map.get_pipe(1, 3).pipe_type, &Direction::Right));
@@ -498,8 +416,8 @@ mod tests {
     #[test]
     fn test_pipes2() {
         // Do a quick test here
-        let input_lines: Vec<String> = generic::read_in_file((INPUTS_FOLDER.to_owned() + "/input_example_2.txt").as_str());
-        let map: PipeMap = PipeMap::from_input_lines(&input_lines);
+        let input_lines: Vec<String> = generic::read_in_file((INPUTS_FOLDER.to_owned() + "/input_example_2.txt").as_str()).unwrap();
+        let map: PipeMap = PipeMap::from_input_lines(&input_lines).unwrap();
         let direction: Direction = Direction::Up;
 
         assert!(map.get_pipe(3, 1).pipe_type.connects(&map.get_pipe(2, 1).pipe_type, &direction) == false);
@@ -526,64 +444,73 @@ mod tests {
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day10>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 4);
+        answers::assert_answer(10, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2_part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_2.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_2.txt";
+        let answer = generic::solve::<Day10>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 8);
+        answers::assert_answer(10, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day10>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 6903);
+        answers::assert_answer(10, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_1_part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day10>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 1);
+        answers::assert_answer(10, 2, &input_filename, answer);
     }
 
     #[test]
     fn example_2_part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_2.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_2.txt";
+        let answer = generic::solve::<Day10>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 1);
+        answers::assert_answer(10, 2, &input_filename, answer);
     }
 
     #[test]
     fn example_3_part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_3.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_3.txt";
+        let answer = generic::solve::<Day10>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 4);
+        answers::assert_answer(10, 2, &input_filename, answer);
     }
 
     #[test]
     fn example_4_part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_4.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_4.txt";
+        let answer = generic::solve::<Day10>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 8);
+        answers::assert_answer(10, 2, &input_filename, answer);
     }
 
     #[test]
     fn example_5_part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_5.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_5.txt";
+        let answer = generic::solve::<Day10>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 10);
+        answers::assert_answer(10, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day10>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 265);
+        answers::assert_answer(10, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
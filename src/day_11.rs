@@ -1,11 +1,14 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_11";
 
 #[warn(dead_code)]
-use crate::generic;
-use std::collections::HashSet;
+use crate::error::AocError;
+use crate::generic::direction::Position;
+use crate::generic::{Grid, Solution};
+use crate::log::{self, Level};
+use crate::trace;
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
-enum DataType {
+pub enum DataType {
     EmptySpace,
     Galaxy,
 }
@@ -26,98 +29,97 @@ impl DataType {
     }
 }
 
-#[derive(Debug, Eq,PartialEq, Clone)]
-struct Galaxy {
-    row: usize,
-    col: usize,
+#[derive(Debug)]
+pub struct GalaxyMap {
+    pub actual_map: Grid<DataType>,
+    pub galaxies: Vec<Position>,
+    pub rows_blank: Vec<bool>,
+    pub cols_blank: Vec<bool>,
 }
 
-impl Galaxy {
-    fn distance(&self, other: &Self) -> usize {
-        let horizontal_distance = self.col.abs_diff(other.col);
-        let vertical_distance = self.row.abs_diff(other.row);
+impl GalaxyMap {
+    pub fn from_input_lines(input_lines: &[String]) -> Result<Self, AocError> {
+        let first_map: Grid<DataType> = Grid::parse(input_lines, |x| Ok(DataType::from_char(x)))?;
+        let rows_blank = first_map.iter_rows().map(|x| x.iter().all(|y| *y == DataType::EmptySpace)).collect::<Vec<bool>>();
+        let cols_blank = first_map.iter_columns().map(|mut x| x.all(|y| *y == DataType::EmptySpace)).collect::<Vec<bool>>();
+
+        let galaxies: Vec<Position> = first_map.enumerate()
+            .filter(|(_, x)| **x == DataType::Galaxy)
+            .map(|(position, _)| Position::from(position))
+            .collect();
 
-        return horizontal_distance + vertical_distance;
+        Ok(Self {
+            actual_map: first_map,
+            galaxies,
+            rows_blank,
+            cols_blank,
+        })
     }
-}
 
-#[derive(Debug)]
-struct GalaxyMap {
-    actual_map: Vec<Vec<DataType>>,
-    galaxies: Vec<Galaxy>,
-}
+    pub fn get_expanded_galaxies(&self, empty_space_add: usize) -> Vec<Position> {
+        // Every blank row and column gets empty_space_add extra copies of itself.
+        let blank_rows_before = |row: usize| self.rows_blank[..row].iter().filter(|x| **x).count();
+        let blank_cols_before = |col: usize| self.cols_blank[..col].iter().filter(|x| **x).count();
 
-impl GalaxyMap {
-    fn from_input_lines(input_lines: &Vec<String>, empty_space_add: usize) -> Self {
-        let first_map = input_lines.iter().map(|x| x.chars().map(|x| DataType::from_char(x)).collect::<Vec<DataType>>()).collect::<Vec<Vec<DataType>>>();
-        let rows_blank = first_map.iter().map(|x| HashSet::<&DataType>::from_iter(x).len() == 1).collect::<Vec<bool>>();
-        let mut cols_blank: Vec<bool> = Vec::new();
-        
-        for col in 0..first_map[0].len() {
-            cols_blank.push(HashSet::<DataType>::from_iter(first_map.iter().map(|x| x[col].clone())).len() == 1);
-        }
+        self.galaxies.iter().map(|g| Position {
+            row: g.row + blank_rows_before(g.row) * empty_space_add,
+            col: g.col + blank_cols_before(g.col) * empty_space_add,
+        }).collect()
+    }
 
-        let mut galaxies: Vec<Galaxy> = Vec::new();
-        let mut row_count: usize = 0;
-        
-        for row in 0..first_map.len() {
-            let mut col_count: usize = 0;
-            for col in 0..first_map[0].len() {
-                if first_map[row][col] == DataType::Galaxy {
-                    galaxies.push(Galaxy { row: row_count, col: col_count })
-                }
-                if cols_blank[col] {
-                    col_count += empty_space_add;
-                }
-                col_count += 1;
-            }
-            
-            if rows_blank[row] {
-                row_count += empty_space_add;
+    pub fn get_total_distance(&self, empty_space_add: usize) -> usize {
+        let galaxies = self.get_expanded_galaxies(empty_space_add);
+        let mut all_distances: usize = 0;
+
+        for i in 0..galaxies.len() {
+            for j in (i+1)..galaxies.len() {
+                all_distances += galaxies[i].manhattan_distance(&galaxies[j]);
             }
-            row_count += 1;
         }
-        
 
-        return Self {
-            actual_map: first_map,
-            galaxies: galaxies,
-        }
+        all_distances
     }
 
-    fn print_map(&self) {
-        for row in 0..self.actual_map.len() {
-            let mut row_string = String::new();
-            for col in 0..self.actual_map[0].len() {
-                row_string.push(self.actual_map[row][col].as_char());
-            }
-            println!("{}", row_string);
+    fn log_map(&self) {
+        if !log::enabled(Level::Trace, module_path!()) {
+            return;
+        }
+
+        for row in self.actual_map.iter_rows() {
+            trace!("{}", row.iter().map(DataType::as_char).collect::<String>());
         }
     }
 }
 
 
-fn solve_puzzle(input_filename: String, _part_2: bool, empty_space_add: usize) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let galaxy_map: GalaxyMap = GalaxyMap::from_input_lines(&input_lines, empty_space_add);
+pub struct Day11;
 
-    galaxy_map.print_map();
+impl Solution for Day11 {
+    type Parsed = GalaxyMap;
+    type Answer = usize;
 
-    let mut all_distances: usize = 0;
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let galaxy_map = GalaxyMap::from_input_lines(&input_lines)?;
+        galaxy_map.log_map();
 
-    for i in 0..galaxy_map.galaxies.len() {
-        for j in (i+1)..galaxy_map.galaxies.len() {
-            all_distances += galaxy_map.galaxies[i].distance(&galaxy_map.galaxies[j]);
-        }
+        Ok(galaxy_map)
     }
 
-    return all_distances;
+    fn part_1(galaxy_map: &Self::Parsed) -> Self::Answer {
+        galaxy_map.get_total_distance(1)
+    }
+
+    fn part_2(galaxy_map: &Self::Parsed) -> Self::Answer {
+        // Each empty row or column becomes one million of them.
+        galaxy_map.get_total_distance(999999)
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
@@ -126,36 +128,41 @@ mod tests {
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false, 1);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day11>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 374);
+        answers::assert_answer(11, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false, 1);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day11>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 10885634);
+        answers::assert_answer(11, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true, 9);
+        let input_lines: Vec<String> = generic::read_in_file((INPUTS_FOLDER.to_owned() + "/input_example_1.txt").as_str()).unwrap();
+        let answer = GalaxyMap::from_input_lines(&input_lines).unwrap().get_total_distance(9);
         println!("Answer = {:?}", answer);
         assert!(answer == 1030);
     }
 
     #[test]
     fn example_3() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true, 99);
+        let input_lines: Vec<String> = generic::read_in_file((INPUTS_FOLDER.to_owned() + "/input_example_1.txt").as_str()).unwrap();
+        let answer = GalaxyMap::from_input_lines(&input_lines).unwrap().get_total_distance(99);
         println!("Answer = {:?}", answer);
         assert!(answer == 8410);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true, 999999);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day11>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 707505470642);
+        answers::assert_answer(11, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
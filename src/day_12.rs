@@ -1,7 +1,9 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_12";
 
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::{self, Solution};
+use crate::{debug, trace};
 use std::collections::{HashSet, HashMap};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -30,23 +32,33 @@ impl SpringState {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct SpringRecord {
-    spring_map: Vec<usize>,
-    spring_mask: Vec<char>,
+pub struct SpringRecord {
+    pub spring_map: Vec<usize>,
+    pub spring_mask: Vec<char>,
 }
 
 impl SpringRecord {
-    fn from_line(input_line: &String) -> Self {
-        let spring_mask: Vec<char> = input_line[0..input_line.find(' ').expect("No space in input line")].chars().collect::<Vec<char>>();
-        let spring_map: Vec<usize> = input_line[(input_line.find(' ').expect("No space in input line")+1)..].split(",").map(|x| x.parse::<usize>().unwrap()).collect();
-
-        return Self {
-            spring_map: spring_map,
-            spring_mask: spring_mask,
-        };
+    /// Parse `<springs> <damaged groups>`, e.g. `???.### 1,1,3`.
+    pub fn from_line(input_line: &str) -> Result<Self, AocError> {
+        let (mask_string, map_string) = input_line.split_once(' ')
+            .ok_or_else(|| AocError::parse(input_line.len() + 1, "expected the damaged groups after the springs"))?;
+        if let Some((i, c)) = mask_string.char_indices().find(|(_, c)| !matches!(c, '.' | '#' | '?')) {
+            return Err(AocError::parse(i + 1, format!("'{}' is not a spring", c)));
+        }
+
+        let spring_map: Vec<usize> = generic::parse_numbers(map_string, ',').map_err(|e| e.offset_column(mask_string.len() + 1))?;
+        let record = Self { spring_map, spring_mask: mask_string.chars().collect() };
+        if record.spring_map.is_empty() {
+            return Err(AocError::parse(mask_string.len() + 2, "expected at least one damaged group"));
+        }
+        if record.get_min_length() > record.get_mask_length() {
+            return Err(AocError::parse(1, format!("the damaged groups need {} springs, but there are {}", record.get_min_length(), record.get_mask_length())));
+        }
+
+        Ok(record)
     }
 
-    fn expand_spring(mut self) -> Self {
+    pub fn expand_spring(mut self) -> Self {
         // used for part 2.  
         self.spring_mask = vec![String::from_iter(self.spring_mask); 5].join("?").chars().collect::<Vec<char>>();
         self.spring_map = vec![self.spring_map; 5].concat();
@@ -96,7 +108,6 @@ impl SpringRecord {
                 spares_index[0] += 1;
             }
 
-            //println!("\t{:?} x = {}", gaps, x);
             all_gaps.push(gaps.clone());
         }
 
@@ -113,13 +124,9 @@ impl SpringRecord {
          */
 
 
-        // println!("\tGetting possibilities for {:?} {:?}", self.spring_mask, self.spring_map);
-
 
         // Check if answer already exists in cache.
         if let Some(result) = possibility_cache.get(self) {
-            // println!("\t\talready exists {:?} {:?}", self.spring_mask, self.spring_map);
-            // println!("\t\t\tpossibilities = {}", result);
             return *result;
         }
         
@@ -138,9 +145,6 @@ impl SpringRecord {
 
             possibility_cache.insert(self.clone(), result);
 
-            // println!("\t\tspring map is empty {:?} {:?}", self.spring_mask, self.spring_map);
-            // println!("\t\t\tpossibilities = {}", result);
-
             return result;
         }
 
@@ -149,9 +153,6 @@ impl SpringRecord {
         if self.spring_mask.len() < self.get_min_length() {
             possibility_cache.insert(self.clone(), 0);
 
-            // println!("\t\tSpring mask is too small {:?} {:?}", self.spring_mask, self.spring_map);
-            // println!("\t\t\tpossibilities = 0");
-
             return 0;
         }
 
@@ -168,9 +169,6 @@ impl SpringRecord {
             let result = new_spring_record.get_possibilities(possibility_cache);
             possibility_cache.insert(self.clone(), result);
 
-            // println!("\t\tfirst is operational - skip ahead {:?} {:?}", self.spring_mask, self.spring_map);
-            // println!("\t\t\tpossibilities = {}", result);
-
             return result;
         }
 
@@ -212,51 +210,52 @@ impl SpringRecord {
         }
 
         possibility_cache.insert(self.clone(), possibilities);
-
-        // println!("\t\tFinished function {:?} {:?}", self.spring_mask, self.spring_map);
-        // println!("\t\t\tpossibilities = {}", possibilities);
+        trace!("{:?} {:?} has {} possibilities", self.spring_mask, self.spring_map, possibilities);
 
         return possibilities;
     }
 }
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut spring_records = input_lines.iter().map(|x| SpringRecord::from_line(x)).collect::<Vec<SpringRecord>>();
-
-    if part_2 {
-        spring_records = spring_records.iter().map(|x| x.clone().expand_spring()).collect::<Vec<SpringRecord>>();
-    }
-
+pub fn get_total_possibilities(spring_records: &[SpringRecord]) -> usize {
     let mut total_sum: usize = 0;
-    let mut index: usize = 0;
-
-    println!("started\n");
 
     let mut possibility_cache: HashMap<SpringRecord, usize> = HashMap::new();
-    for s in spring_records {
-        //println!("gaps = {}, spare = {}", s.spring_map.len() + 1, s.get_spares());
-        //println!("Spring set = {:?}", s.spring_map);
-        //println!("Spring mask = {:?}", s.spring_mask);
-        print!("\rdoing {}/{}", index + 1, input_lines.len());
+    for (index, s) in spring_records.iter().enumerate() {
+        debug!("Record {}/{}", index + 1, spring_records.len());
         total_sum += s.get_possibilities(&mut possibility_cache);
-        index += 1;
     }
-    println!("\nDone");
 
+    total_sum
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Parsed = Vec<SpringRecord>;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        input_lines.iter().enumerate().map(|(i, x)| SpringRecord::from_line(x).map_err(|e| e.at_line(i + 1))).collect()
+    }
 
+    fn part_1(spring_records: &Self::Parsed) -> Self::Answer {
+        get_total_possibilities(spring_records)
+    }
 
-    return total_sum;
+    fn part_2(spring_records: &Self::Parsed) -> Self::Answer {
+        get_total_possibilities(&spring_records.iter().map(|x| x.clone().expand_spring()).collect::<Vec<SpringRecord>>())
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     fn check_spring(input_line: &str, answer: usize, part_2: bool) {
         let input_lines: Vec<String> = vec![input_line.to_string()];
-        let mut spring_records = input_lines.iter().map(|x| SpringRecord::from_line(x)).collect::<Vec<SpringRecord>>();
+        let mut spring_records = input_lines.iter().map(|x| SpringRecord::from_line(x).unwrap()).collect::<Vec<SpringRecord>>();
         if part_2 {
             spring_records = spring_records.iter().map(|x| x.clone().expand_spring()).collect::<Vec<SpringRecord>>();
         }
@@ -302,31 +301,47 @@ mod tests {
         check_spring("?###???????? 3,2,1", 506250, true);
     }
 
+    #[test]
+    fn bad_records() {
+        assert_eq!(SpringRecord::from_line("???.###").err(), Some(AocError::parse(8, "expected the damaged groups after the springs")));
+        assert_eq!(SpringRecord::from_line("??x.### 1,1,3").err(), Some(AocError::parse(3, "'x' is not a spring")));
+        assert_eq!(SpringRecord::from_line("???.### 1,x,3").err(), Some(AocError::parse(11, "'x' is not a number")));
+        assert_eq!(SpringRecord::from_line("???.### ").err(), Some(AocError::parse(9, "expected at least one damaged group")));
+        assert_eq!(SpringRecord::from_line("?.# 2,1").err(), Some(AocError::parse(1, "the damaged groups need 4 springs, but there are 3")));
+
+        let input_lines: Vec<String> = vec!["???.### 1,1,3".to_string(), "???.### 1,1,".to_string(), "#".to_string()];
+        assert_eq!(Day12::parse(input_lines).err().map(|e| e.to_string()), Some("3:2: expected the damaged groups after the springs".to_string()));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day12>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 21);
+        answers::assert_answer(12, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day12>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 6827);
+        answers::assert_answer(12, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day12>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 525152);
+        answers::assert_answer(12, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day12>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 1537505634471);
+        answers::assert_answer(12, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
@@ -2,21 +2,20 @@ const INPUTS_FOLDER: &str = "src/inputs/day_13";
 
 use std::fmt::Display;
 
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::{Grid, Solution};
 
 #[derive(Clone, Eq, PartialEq, Hash)]
-enum Terrain {
+pub enum Terrain {
     Ash,
     Rock,
 }
 
-impl From<char> for Terrain {
-    fn from(input_char: char) -> Self {
-        match input_char {
-            '.' => Self::Ash,
-            '#' => Self::Rock,
-            _ => panic!("Bad input type"),
-        }
+fn terrain(value: char) -> Result<Terrain, AocError> {
+    match value {
+        '.' => Ok(Terrain::Ash),
+        '#' => Ok(Terrain::Rock),
+        _ => Err(AocError::parse(0, format!("'{}' is not ash or rock", value))),
     }
 }
 
@@ -32,83 +31,71 @@ impl Display for Terrain {
 }
 
 
-struct Pattern {
-    rows: Vec<Vec<Terrain>>,
-    cols: Vec<Vec<Terrain>>,
-}
-
-impl From<Vec<String>> for Pattern {
-    fn from(input_lines: Vec<String>) -> Self {
-        let rows = input_lines.iter().map(|x| x.chars().map(|x| Terrain::from(x)).collect::<Vec<Terrain>>()).collect::<Vec<Vec<Terrain>>>();
-        let cols = (0..rows[0].len()).map(|i| rows.iter().map(|row| row[i].clone()).collect::<Vec<Terrain>>()).collect::<Vec<Vec<Terrain>>>();
-
-        return Self { rows: rows, cols: cols };
-    }
+pub struct Pattern {
+    pub terrain: Grid<Terrain>,
 }
 
 impl Display for Pattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let display_string = self.rows
-            .iter()
-            .map(|x| x.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join(""))
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        write!(f, "{}", display_string)
+        write!(f, "{}", self.terrain)
     }
 }
 
 impl Pattern {
-    fn get_mirror(map_lines: &Vec<Vec<Terrain>>) -> Option<usize> {
-        // map_lines can be either rows or cols in the map.
+    pub fn from_lines(input_lines: &[String]) -> Result<Self, AocError> {
+        Ok(Self { terrain: Grid::parse(input_lines, terrain)? })
+    }
+
+    fn get_mirror(map: &Grid<Terrain>) -> Option<usize> {
+        // Looks for a mirror between rows. Transpose the map to look between columns.
 
-        let mut last_line: &Vec<Terrain> = map_lines.first().expect("Map lines input is empty!");
+        let mut last_line: &[Terrain] = map.row(0);
         let mut line_index: usize = 1;
-        while line_index < map_lines.len() {
-            if &map_lines[line_index] == last_line {
-                let short_side_length: usize = line_index.min(map_lines.len() - line_index);
-                let sides_match: bool = (0..short_side_length).all(|i| map_lines[line_index-1-i] == map_lines[line_index + i]);
+        while line_index < map.rows() {
+            if map.row(line_index) == last_line {
+                let short_side_length: usize = line_index.min(map.rows() - line_index);
+                let sides_match: bool = (0..short_side_length).all(|i| map.row(line_index-1-i) == map.row(line_index + i));
                 if sides_match {
                     return Some(line_index);
                 }
             }
-            last_line = &map_lines[line_index];
+            last_line = map.row(line_index);
             line_index += 1;
         }
 
         return None;
     }
 
-    fn get_left_columns(&self) -> Option<usize> {
-        return Self::get_mirror(&self.cols);
+    pub fn get_left_columns(&self) -> Option<usize> {
+        Self::get_mirror(&self.terrain.transpose())
     }
 
-    fn get_top_rows(&self) -> Option<usize> {
-        return Self::get_mirror(&self.rows);
+    pub fn get_top_rows(&self) -> Option<usize> {
+        Self::get_mirror(&self.terrain)
     }
 
-    fn get_mirror_with_smude(map_lines: &Vec<Vec<Terrain>>) -> Option<usize> {
-        // map_lines can be either rows or cols in the map.
+    fn get_mirror_with_smude(map: &Grid<Terrain>) -> Option<usize> {
+        // Looks for a mirror between rows. Transpose the map to look between columns.
 
-        let mut last_line: &Vec<Terrain> = map_lines.first().expect("Map lines input is empty!");
+        let mut last_line: &[Terrain] = map.row(0);
         let mut line_index: usize = 1;
         let mut check_all: bool = false;
         let mut smudge_fixed: bool = false;
 
-        while line_index < map_lines.len() {
-            if &map_lines[line_index] == last_line {
+        while line_index < map.rows() {
+            if map.row(line_index) == last_line {
                 check_all = true;
-            } else if !smudge_fixed && almost_equal(&last_line, &map_lines[line_index]) {
+            } else if !smudge_fixed && almost_equal(last_line, map.row(line_index)) {
                 check_all = true;
                 smudge_fixed = true;
             }
 
             if check_all {
-                let short_side_length: usize = line_index.min(map_lines.len() - line_index);
+                let short_side_length: usize = line_index.min(map.rows() - line_index);
                 let mut sides_match: bool = true;
                 for i in 1..short_side_length {
-                    if map_lines[line_index-1-i] != map_lines[line_index + i] {
-                        if !smudge_fixed && almost_equal(&map_lines[line_index-1-i], &map_lines[line_index+i]) {
+                    if map.row(line_index-1-i) != map.row(line_index + i) {
+                        if !smudge_fixed && almost_equal(map.row(line_index-1-i), map.row(line_index+i)) {
                             smudge_fixed = true;
                         } else {
                             sides_match = false;
@@ -124,99 +111,114 @@ impl Pattern {
 
             smudge_fixed = false;
             check_all = false;
-            last_line = &map_lines[line_index];
+            last_line = map.row(line_index);
             line_index += 1;
         }
 
         return None;
     }
 
-    fn get_left_columns_with_smudge(&self) -> Option<usize> {
-        return Self::get_mirror_with_smude(&self.cols);
+    pub fn get_left_columns_with_smudge(&self) -> Option<usize> {
+        Self::get_mirror_with_smude(&self.terrain.transpose())
     }
 
-    fn get_top_rows_with_smudge(&self) -> Option<usize> {
-        return Self::get_mirror_with_smude(&self.rows);
+    pub fn get_top_rows_with_smudge(&self) -> Option<usize> {
+        Self::get_mirror_with_smude(&self.terrain)
     }
 }
 
-fn almost_equal(first: &Vec<Terrain>, second: &Vec<Terrain>) -> bool {
-    // println!("Checking {}", first.iter().map(|x| format!("{}", x)).collect::<String>());
-    // println!("\tagainst {}", second.iter().map(|x| format!("{}", x)).collect::<String>());
-    // println!("\tresult = {}", (0..first.len()).map(|i| (first[i] != second[i]) as usize).sum::<usize>());
+fn almost_equal(first: &[Terrain], second: &[Terrain]) -> bool {
     return (0..first.len()).map(|i| (first[i] != second[i]) as usize).sum::<usize>() == 1;
 }
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut index: usize = 0;
-    let mut start_index: usize = 0;
-    let mut patterns: Vec<Pattern> = Vec::new();
-    while index < input_lines.len() {
-        if input_lines[index] == "" {
-            patterns.push(Pattern::from(input_lines[start_index..index].to_vec()));
-            start_index = index + 1;
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Parsed = Vec<Pattern>;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let mut index: usize = 0;
+        let mut start_index: usize = 0;
+        let mut patterns: Vec<Pattern> = Vec::new();
+        while index < input_lines.len() {
+            if input_lines[index] == "" {
+                patterns.push(Pattern::from_lines(&input_lines[start_index..index]).map_err(|e| e.offset_line(start_index))?);
+                start_index = index + 1;
+            }
+            index += 1;
         }
-        index += 1;
+        patterns.push(Pattern::from_lines(&input_lines[start_index..]).map_err(|e| e.offset_line(start_index))?);
+
+        Ok(patterns)
     }
-    patterns.push(Pattern::from(input_lines[start_index..].to_vec()));
 
-    let mut result: usize = 0;
-    if !part_2 {
+    fn part_1(patterns: &Self::Parsed) -> Self::Answer {
+        let mut result: usize = 0;
         result += patterns.iter().map(|x| x.get_left_columns()).filter(|x| x.is_some()).map(|x| x.unwrap()).sum::<usize>();
         result += patterns.iter().map(|x| x.get_top_rows()).filter(|x| x.is_some()).map(|x| x.unwrap() * 100).sum::<usize>();
-    } else {
-        result += patterns.iter().map(|x| x.get_left_columns_with_smudge()).filter(|x| x.is_some()).map(|x| x.unwrap()).sum::<usize>();
-        result += patterns.iter().map(|x| x.get_top_rows_with_smudge()).filter(|x| x.is_some()).map(|x| x.unwrap() * 100).sum::<usize>();
+
+        result
     }
 
-    // for p in patterns {
-    //     if let Some(left_cols) = p.get_left_columns_with_smudge() {
-    //         println!("Left is {}", left_cols);
-    //     }
-    //     if let Some(top_rows) = p.get_top_rows_with_smudge() {
-    //         println!("Top is {}", top_rows);
-    //     }
-    // }
+    fn part_2(patterns: &Self::Parsed) -> Self::Answer {
+        let mut result: usize = 0;
+        result += patterns.iter().map(|x| x.get_left_columns_with_smudge()).filter(|x| x.is_some()).map(|x| x.unwrap()).sum::<usize>();
+        result += patterns.iter().map(|x| x.get_top_rows_with_smudge()).filter(|x| x.is_some()).map(|x| x.unwrap() * 100).sum::<usize>();
 
-    return result;
+        result
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
         // Do a quick test here
     }
 
+    #[test]
+    fn bad_terrain() {
+        assert!(terrain('#') == Ok(Terrain::Rock));
+        assert!(terrain('x') == Err(AocError::parse(0, "'x' is not ash or rock")));
+
+        let input_lines: Vec<String> = ["#.#", "..#", "", "##.", "#x."].iter().map(|x| x.to_string()).collect();
+        assert_eq!(Day13::parse(input_lines).err().map(|e| e.to_string()), Some("5:2: 'x' is not ash or rock".to_string()));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day13>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 405);
+        answers::assert_answer(13, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day13>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 30705);
+        answers::assert_answer(13, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day13>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 400);
+        answers::assert_answer(13, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day13>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 44615);
+        answers::assert_answer(13, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
@@ -2,19 +2,14 @@ const INPUTS_FOLDER: &str = "src/inputs/day_14";
 
 use std::{fmt::Display, collections::HashMap};
 
-use crate::generic;
-
-#[derive(Debug, Clone, Eq, PartialEq)]
-enum Direction {
-    North,
-    West,
-    South,
-    East,
-}
-
+use crate::error::AocError;
+use crate::generic::cycle;
+use crate::generic::direction::Direction;
+use crate::generic::{Grid, Solution};
+use crate::{debug, trace};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum Rock {
+pub enum Rock {
     Round,
     Cube,
     Ground,
@@ -50,77 +45,54 @@ impl Display for RockWrapper {
 }
 
 #[derive(Hash, Eq, PartialEq, Clone)]
-struct Platform {
-    rows: Vec<Vec<Rock>>,
-}
-
-impl From<Vec<String>> for Platform {
-    fn from(value: Vec<String>) -> Self {
-        Self {
-            rows: value.iter().map(|row| row.chars().map(|x| Rock::from(x)).collect::<Vec<Rock>>()).collect::<Vec<Vec<Rock>>>(),
-        }
-    }
+pub struct Platform {
+    pub rocks: Grid<Rock>,
 }
 
 impl Display for Platform {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let display_string = self.rows
-            .iter()
-            .map(|x| x.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join(""))
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        write!(f, "{}", display_string)
+        write!(f, "{}", self.rocks)
     }
 }
 
 impl Platform {
-    fn get_north_load(&self) -> usize {
-        let cols = (0..self.rows[0].len()).map(|i| self.rows.iter().map(|row| row[i].clone()).collect::<Vec<Rock>>()).collect::<Vec<Vec<Rock>>>();
-        let max_level: usize = cols[0].len();
-        let mut total_count: usize = 0;
-
-        for col in cols.iter() {
-            total_count += (0..col.len()).filter(|i| col[*i] == Rock::Round).map(|i| col.len() - i).sum::<usize>();
-        }
+    pub fn from_lines(input_lines: &[String]) -> Result<Self, AocError> {
+        Ok(Self { rocks: Grid::parse(input_lines, |x| Ok(Rock::from(x)))? })
+    }
 
-        return total_count;
+    pub fn get_north_load(&self) -> usize {
+        self.rocks.enumerate().filter(|(_, x)| **x == Rock::Round).map(|((row, _), _)| self.rocks.rows() - row).sum()
     }
 
     fn tilt_platform(&mut self, direction: Direction, cache: &mut HashMap<Vec<Rock>, Vec<Rock>>) {
+        // Rocks roll towards the start of the line given to tilt_vec, so lines are read starting
+        // from the side they roll towards.
         match direction {
-            Direction::North => {
-                for col_index in 0..self.rows[0].len() {
-                    let new_vec = Self::tilt_vec(&self.rows.iter().map(|x| x[col_index].clone()).collect::<Vec<Rock>>(), cache);
-                    for row_index in 0..self.rows.len() {
-                        self.rows[row_index][col_index] = new_vec[row_index].clone();
-                    }
+            Direction::Up => {
+                for col_index in 0..self.rocks.cols() {
+                    let new_vec = Self::tilt_vec(&self.rocks.column(col_index).cloned().collect::<Vec<Rock>>(), cache);
+                    self.rocks.column_mut(col_index).zip(new_vec).for_each(|(x, rock)| *x = rock);
                 }
             },
-            Direction::West => {
-                for row_index in 0..self.rows.len() {
-                    let new_vec = Self::tilt_vec(&self.rows[row_index], cache);
-                    self.rows[row_index] = new_vec;
+            Direction::Left => {
+                for row_index in 0..self.rocks.rows() {
+                    let new_vec = Self::tilt_vec(&self.rocks.row(row_index).to_vec(), cache);
+                    self.rocks.row_mut(row_index).clone_from_slice(&new_vec);
                 }
             },
-            Direction::South => {
-                // South is bottom to top. Reverse the input to tilt and reverse the output.
-                for col_index in 0..self.rows[0].len() {
-                    let new_vec = Self::tilt_vec(&self.rows.iter().rev().map(|x| x[col_index].clone()).collect::<Vec<Rock>>(), cache)
-                        .into_iter().rev().collect::<Vec<Rock>>();
-                    for row_index in 0..self.rows.len() {
-                        self.rows[row_index][col_index] = new_vec[row_index].clone();
-                    }
+            Direction::Down => {
+                for col_index in 0..self.rocks.cols() {
+                    let new_vec = Self::tilt_vec(&self.rocks.column(col_index).rev().cloned().collect::<Vec<Rock>>(), cache);
+                    self.rocks.column_mut(col_index).rev().zip(new_vec).for_each(|(x, rock)| *x = rock);
                 }
             },
-            Direction::East => {
-                // East rolls the rocks to the right. Reverse the input to tilt and reverse the output.
-                for row_index in 0..self.rows.len() {
-                    let new_vec = Self::tilt_vec(&self.rows[row_index].clone().into_iter().rev().collect::<Vec<Rock>>(), cache)
-                        .into_iter().rev().collect::<Vec<Rock>>();
-                    self.rows[row_index] = new_vec;
+            Direction::Right => {
+                for row_index in 0..self.rocks.rows() {
+                    let new_vec = Self::tilt_vec(&self.rocks.row(row_index).iter().rev().cloned().collect::<Vec<Rock>>(), cache);
+                    self.rocks.row_mut(row_index).iter_mut().rev().zip(new_vec).for_each(|(x, rock)| *x = rock);
                 }
             },
+            _ => panic!("Can only tilt the platform up, down, left or right, not {:?}", direction),
         }
     }
 
@@ -148,101 +120,57 @@ impl Platform {
     }
 
     fn spin_platform(&mut self, cache: &mut HashMap<Vec<Rock>, Vec<Rock>>) {
-        for d in vec![Direction::North, Direction::West, Direction::South, Direction::East] {
+        // North, west, south then east.
+        for d in [Direction::Up, Direction::Left, Direction::Down, Direction::Right] {
             self.tilt_platform(d, cache);
         }
     }
 
-    fn spin_cycle(&self, cycles: usize) -> Self {
+    pub fn spin_cycle(&self, cycles: usize) -> Self {
         let mut cache: HashMap<Vec<Rock>, Vec<Rock>> = HashMap::new();
-        let mut platform_cache: HashMap<Self, Self> = HashMap::new();
-        let mut platform_index: HashMap<Self, usize> = HashMap::new();
-        let mut platform_list: Vec<Self> = Vec::new();
-        let mut found_repeat: bool = false;
-        let mut start_index: usize = 0;
-        let mut platform_loop: Vec<usize> = Vec::new();
-        let mut loop_start: usize = 0;
-
-
-        println!("Running Spins.");
-        println!("");
-
-        let mut current_platform: Self = self.clone();
-        for i in 0..cycles {
-            if let Some(spun_platform) = platform_cache.get(&current_platform) {
-                println!("Platform {} comes from platform {}", i, platform_index[&current_platform]);
-                
-                current_platform = spun_platform.clone();
-                if !found_repeat {
-                    found_repeat = true;
-                    loop_start = i;
-                    start_index = platform_index[&current_platform];
-
-                    let mut current_index = start_index;
-                    platform_loop.push(current_index);
-                    current_platform = platform_cache.get(&current_platform).expect("Platform not in HashMap!").clone();
-                    current_index = platform_index[&current_platform];
-                    while current_index != start_index {
-                        platform_loop.push(current_index);
-                        current_platform = platform_cache.get(&current_platform).expect("Platform not in HashMap!").clone();
-                        current_index = platform_index[&current_platform];
-                    }
-                    break;
-                }
-            } else {
-                let pre_spin_platform = current_platform.clone();
-
-                current_platform.spin_platform(&mut cache);
-
-                // println!("");
-                // println!("Prespin");
-                // println!("{}", pre_spin_platform);
-                // println!("");
-                // println!("After spin cycles");
-                // println!("{}", current_platform);
-
-                platform_cache.insert(pre_spin_platform.clone(), current_platform.clone());
-                platform_index.insert(pre_spin_platform.clone(), i);
-                platform_list.push(pre_spin_platform);
-            }
-
-            // println!("");
-            // println!("After {} cycles", i+1);
-            // println!("{}", current_platform);
-        }
-
-        if found_repeat {
-            println!("{:?}", platform_loop);
-            for x in 0..platform_list.len() {
-                println!("Platform {} = {}", x, platform_list[x].get_north_load());
-            }
-            return platform_list[platform_loop[(cycles - loop_start - 1) % platform_loop.len()]].clone();
+        let iterated = cycle::iterate(self.clone(), cycles, |platform| {
+            let mut platform = platform.clone();
+            platform.spin_platform(&mut cache);
+            trace!("Spun to:\n{}", platform);
+            platform
+        });
+
+        if let Some(repeat) = iterated.cycle {
+            debug!("Platforms repeat every {} cycles from cycle {}", repeat.length, repeat.start);
         }
 
-        return current_platform;
+        iterated.state
     }
 }
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut platform = Platform::from(input_lines);
+pub struct Day14;
 
-    // println!("{}", platform);
+impl Solution for Day14 {
+    type Parsed = Platform;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        Platform::from_lines(&input_lines)
+    }
 
-    if part_2 {
-        platform = platform.spin_cycle(1000000000);
-    } else {
+    fn part_1(platform: &Self::Parsed) -> Self::Answer {
+        let mut platform = platform.clone();
         let mut cache: HashMap<Vec<Rock>, Vec<Rock>> = HashMap::new();
-        platform.tilt_platform(Direction::North, &mut cache);
+        platform.tilt_platform(Direction::Up, &mut cache);
+
+        platform.get_north_load()
     }
 
-    return platform.get_north_load();
+    fn part_2(platform: &Self::Parsed) -> Self::Answer {
+        platform.spin_cycle(1000000000).get_north_load()
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
@@ -265,31 +193,48 @@ mod tests {
         println!("new {}", RockWrapper(new_vec));
     }
 
+    #[test]
+    fn spin_cycles() {
+        let input_lines: Vec<String> = generic::read_in_file(&(INPUTS_FOLDER.to_owned() + "/input_example_1.txt")).unwrap();
+        let mut platform = Platform::from_lines(&input_lines).unwrap();
+        let start = platform.clone();
+        let mut cache: HashMap<Vec<Rock>, Vec<Rock>> = HashMap::new();
+
+        for cycles in 0..20 {
+            assert!(start.spin_cycle(cycles) == platform, "after {} cycles", cycles);
+            platform.spin_platform(&mut cache);
+        }
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day14>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 136);
+        answers::assert_answer(14, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day14>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 108826);
+        answers::assert_answer(14, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day14>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 64);
+        answers::assert_answer(14, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day14>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 99291);
+        answers::assert_answer(14, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
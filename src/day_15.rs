@@ -2,10 +2,13 @@ const INPUTS_FOLDER: &str = "src/inputs/day_15";
 
 use std::{collections::{HashMap, HashSet}, fmt::Display};
 
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::Solution;
+use crate::log::{self, Level};
+use crate::debug;
 
-#[derive(Clone, Eq, PartialEq, Hash)]
-enum Operation {
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Operation {
     Dash,
     Equals,
 }
@@ -20,26 +23,31 @@ impl Display for Operation {
     } 
 }
 
-impl From<char> for Operation {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Operation {
+    type Error = AocError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '-' => Self::Dash,
-            '=' => Self::Equals,
-            _ => panic!("Bad character given")
+            '-' => Ok(Self::Dash),
+            '=' => Ok(Self::Equals),
+            _ => Err(AocError::parse(0, format!("'{}' is not an operation", value))),
         }
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Hash)]
-struct Step {
-    operation_type: Operation,
-    label: String,
-    focal_length: usize,
-    hash_value: usize,
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Step {
+    pub operation_type: Operation,
+    pub label: String,
+    pub focal_length: usize,
+    pub hash_value: usize,
 }
 
-impl From<&String> for Step {
-    fn from(value: &String) -> Self {
+impl TryFrom<&str> for Step {
+    type Error = AocError;
+
+    /// Parse `<label>-` or `<label>=<focal length>`.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let operation_index: usize;
         let focal_length: usize;
         if value.contains('-') {
@@ -47,17 +55,22 @@ impl From<&String> for Step {
             focal_length = 0;
         } else if value.contains('=') {
             operation_index = value.find('=').unwrap();
-            focal_length = value[(operation_index + 1)..].parse::<usize>().expect("Focal length is not a number!");
+            focal_length = value[(operation_index + 1)..].parse::<usize>()
+                .map_err(|_| AocError::parse(operation_index + 2, format!("focal length '{}' is not a number", &value[(operation_index + 1)..])))?;
         } else {
-            panic!("Instruction does not have operation {}", value);
+            return Err(AocError::parse(1, format!("step '{}' has no '-' or '=' operation", value)));
         }
 
-        return Self {
-            operation_type: Operation::from(value.chars().collect::<Vec<char>>()[operation_index]),
+        if operation_index == 0 {
+            return Err(AocError::parse(1, "step has no label"));
+        }
+
+        Ok(Self {
+            operation_type: Operation::try_from(value.chars().collect::<Vec<char>>()[operation_index]).map_err(|e| e.offset_column(operation_index + 1))?,
             label: value[..operation_index].to_string(),
             focal_length: focal_length,
             hash_value: hash_algorithm(&value[..operation_index].to_string()),
-        };
+        })
     }
 }
 
@@ -68,14 +81,14 @@ impl Display for Step {
 }
 
 #[derive(Clone)]
-struct LightBox {
-    step_labels: HashMap<String, Step>,
-    step_order: HashMap<Step, usize>,
-    step_max_index: usize,
+pub struct LightBox {
+    pub step_labels: HashMap<String, Step>,
+    pub step_order: HashMap<Step, usize>,
+    pub step_max_index: usize,
 }
 
 impl LightBox {
-    fn new() -> Self {
+    pub fn new() -> Self {
         return Self {
             step_labels: HashMap::new(),
             step_order: HashMap::new(),
@@ -83,7 +96,7 @@ impl LightBox {
         };
     }
 
-    fn add_step(&mut self, step: &Step) {
+    pub fn add_step(&mut self, step: &Step) {
         match step.operation_type {
             Operation::Dash => {
                 if let Some(removed_step) = self.step_labels.remove(&step.label) {
@@ -106,7 +119,7 @@ impl LightBox {
         }
     }
 
-    fn get_product_value(&self) -> usize {
+    pub fn get_product_value(&self) -> usize {
         let mut box_steps: Vec<Step> = self.step_order.keys().map(|x| x.clone()).collect::<Vec<Step>>();
         box_steps.sort_by_key(|x| self.step_order[x]);
 
@@ -114,7 +127,7 @@ impl LightBox {
     }
 }
 
-fn hash_algorithm(input: &String) -> usize {
+pub fn hash_algorithm(input: &String) -> usize {
     let mut result: usize = 0;
     for c in input.chars() {
         result += c as usize;
@@ -125,30 +138,55 @@ fn hash_algorithm(input: &String) -> usize {
     return result;
 }
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let steps: Vec<String> = input_lines.first().expect("Input lines is empty!").split(',').map(|x| x.to_string()).collect::<Vec<String>>();
-    let real_steps: Vec<Step> = steps.iter().map(|x| Step::from(x)).collect::<Vec<Step>>();
+/// The initialization sequence, both as written and as parsed steps.
+pub struct InitSequence {
+    pub step_strings: Vec<String>,
+    pub steps: Vec<Step>,
+}
 
-    let mut all_boxes: Vec<LightBox> = vec![LightBox::new(); 256];
+pub struct Day15;
 
-    for step in real_steps {
-        all_boxes[step.hash_value].add_step(&step);
-    }
+impl Solution for Day15 {
+    type Parsed = InitSequence;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let line = input_lines.first().ok_or(AocError::parse(0, "input is empty"))?;
+        let step_strings: Vec<String> = line.split(',').map(|x| x.to_string()).collect::<Vec<String>>();
 
-    for (i, b) in all_boxes.iter().enumerate() {
-        if b.step_labels.len() != 0 {
-            let mut box_steps: Vec<Step> = b.step_order.keys().map(|x| x.clone()).collect::<Vec<Step>>();
-            box_steps.sort_by_key(|x| b.step_order[x]);
-            let steps_string: String = box_steps.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join(" ");
-            println!("Box {}: {}", i, steps_string);
+        let mut steps: Vec<Step> = Vec::new();
+        let mut column: usize = 0;
+        for step_string in step_strings.iter() {
+            steps.push(Step::try_from(step_string.as_str()).map_err(|e| e.offset_column(column).at_line(1))?);
+            column += step_string.len() + 1;
         }
+
+        Ok(InitSequence { step_strings, steps })
+    }
+
+    fn part_1(sequence: &Self::Parsed) -> Self::Answer {
+        sequence.step_strings.iter().map(hash_algorithm).sum()
     }
 
-    if !part_2 {
-        return steps.iter().map(|x| hash_algorithm(x)).sum();
-    } else {
-        return (0..all_boxes.len()).map(|i| (i+1) * all_boxes[i].get_product_value()).sum();
+    fn part_2(sequence: &Self::Parsed) -> Self::Answer {
+        let real_steps: Vec<Step> = sequence.steps.clone();
+
+        let mut all_boxes: Vec<LightBox> = vec![LightBox::new(); 256];
+
+        for step in real_steps {
+            all_boxes[step.hash_value].add_step(&step);
+        }
+
+        for (i, b) in all_boxes.iter().enumerate() {
+            if !b.step_labels.is_empty() && log::enabled(Level::Debug, module_path!()) {
+                let mut box_steps: Vec<Step> = b.step_order.keys().map(|x| x.clone()).collect::<Vec<Step>>();
+                box_steps.sort_by_key(|x| b.step_order[x]);
+                let steps_string: String = box_steps.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join(" ");
+                debug!("Box {}: {}", i, steps_string);
+            }
+        }
+
+        (0..all_boxes.len()).map(|i| (i+1) * all_boxes[i].get_product_value()).sum()
     }
 }
 
@@ -156,6 +194,7 @@ fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
@@ -165,31 +204,48 @@ mod tests {
         assert_eq!(hash_algorithm(&"qp".to_string()), 1);
     }
 
+    #[test]
+    fn bad_steps() {
+        assert_eq!(Step::try_from("rn=1").map(|x| x.focal_length), Ok(1));
+        assert_eq!(Step::try_from("rn=x"), Err(AocError::parse(4, "focal length 'x' is not a number")));
+        assert_eq!(Step::try_from("rn"), Err(AocError::parse(1, "step 'rn' has no '-' or '=' operation")));
+        assert_eq!(Step::try_from("-"), Err(AocError::parse(1, "step has no label")));
+        assert_eq!(Operation::try_from('='), Ok(Operation::Equals));
+        assert_eq!(Operation::try_from('+'), Err(AocError::parse(0, "'+' is not an operation")));
+
+        let error = Day15::parse(vec!["rn=1,cm-,qp=a".to_string()]).err();
+        assert_eq!(error.map(|e| e.to_string()), Some("1:13: focal length 'a' is not a number".to_string()));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day15>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 1320);
+        answers::assert_answer(15, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day15>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 519041);
+        answers::assert_answer(15, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day15>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 145);
+        answers::assert_answer(15, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day15>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 260530);
+        answers::assert_answer(15, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
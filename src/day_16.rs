@@ -1,18 +1,13 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_16";
 
-use std::collections::HashMap;
+use std::collections::HashSet;
 
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::direction::{Direction, Position};
+use crate::generic::{Grid, Solution};
+use crate::debug;
 
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum TileType {
     VerticalPipe,
@@ -37,227 +32,194 @@ impl From<char> for TileType {
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Tile {
     tile_type: TileType,
-    row: usize,
-    col: usize,
     activated: bool,
-    directions: HashMap<Direction, bool>,
+    // Directions beams have already gone through this tile in.
+    directions: HashSet<Direction>,
 }
 
 impl From<char> for Tile {
     fn from(value: char) -> Self {
-        let mut directions_hashmap: HashMap<Direction, bool> = HashMap::new();
-        directions_hashmap.insert(Direction::Up, false);
-        directions_hashmap.insert(Direction::Down, false);
-        directions_hashmap.insert(Direction::Left, false);
-        directions_hashmap.insert(Direction::Right, false);
-        return Self{tile_type: TileType::from(value), row: 0, col: 0, activated: false, directions: directions_hashmap};
+        Self{tile_type: TileType::from(value), activated: false, directions: HashSet::new()}
     }
 }
 
 impl Tile {
-    fn new(value: char, row: usize, col: usize) -> Self {
-        let mut directions_hashmap: HashMap<Direction, bool> = HashMap::new();
-        directions_hashmap.insert(Direction::Up, false);
-        directions_hashmap.insert(Direction::Down, false);
-        directions_hashmap.insert(Direction::Left, false);
-        directions_hashmap.insert(Direction::Right, false);
-        return Self{tile_type: TileType::from(value), row: row, col: col, activated: false, directions: directions_hashmap};
-    }
-
+    /// Send a beam through the tile, returning the directions it leaves in. A beam going the same
+    /// way as one before it goes nowhere new.
     fn activate(&mut self, direction: Direction) -> Vec<Direction> {
-        let mut return_directions: Vec<Direction> = Vec::new();
-        if (self.directions[&direction]) {
-            // Direction already set.
-        } else {
-            self.directions.insert(direction.clone(), true);
-            self.activated = true;
-
-            match self.tile_type {
-                TileType::VerticalPipe => match direction {
-                    Direction::Up => return_directions.push(direction.clone()),
-                    Direction::Down => return_directions.push(direction.clone()),
-                    Direction::Left | Direction::Right => {
-                        return_directions.push(Direction::Up);
-                        return_directions.push(Direction::Down);
-                    },
-                },
-                TileType::HorizontalPipe => match direction {
-                    Direction::Up | Direction::Down => {
-                        return_directions.push(Direction::Left);
-                        return_directions.push(Direction::Right);
-                    },
-                    Direction::Left => return_directions.push(direction.clone()),
-                    Direction::Right => return_directions.push(direction.clone()),
-                },
-                TileType::ForwardSlash => match direction {
-                    Direction::Up => return_directions.push(Direction::Left),
-                    Direction::Down => return_directions.push(Direction::Right),
-                    Direction::Left => return_directions.push(Direction::Up),
-                    Direction::Right => return_directions.push(Direction::Down),
-                }
-                TileType::BackwardSlash => match direction {
-                    Direction::Up => return_directions.push(Direction::Right),
-                    Direction::Down => return_directions.push(Direction::Left),
-                    Direction::Left => return_directions.push(Direction::Down),
-                    Direction::Right => return_directions.push(Direction::Up),
-                }
-                TileType::Empty => return_directions.push(direction.clone()),
-            }
-            
-            self.directions.insert(direction, true);
+        if !self.directions.insert(direction) {
+            return Vec::new();
+        }
+        self.activated = true;
+
+        match self.tile_type {
+            TileType::VerticalPipe if direction.is_horizontal() => vec![Direction::Up, Direction::Down],
+            TileType::HorizontalPipe if direction.is_vertical() => vec![Direction::Left, Direction::Right],
+            TileType::ForwardSlash => match direction {
+                Direction::Up => vec![Direction::Right],
+                Direction::Down => vec![Direction::Left],
+                Direction::Left => vec![Direction::Down],
+                Direction::Right => vec![Direction::Up],
+                _ => Vec::new(),
+            },
+            TileType::BackwardSlash => match direction {
+                Direction::Up => vec![Direction::Left],
+                Direction::Down => vec![Direction::Right],
+                Direction::Left => vec![Direction::Up],
+                Direction::Right => vec![Direction::Down],
+                _ => Vec::new(),
+            },
+            _ => vec![direction],
         }
-
-        return return_directions;
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct TileMap {
-    tiles: Vec<Vec<Tile>>,
+pub struct TileMap {
+    tiles: Grid<Tile>,
 }
 
 impl TileMap {
-    fn new(input_lines: Vec<String>) -> Self {
-        let mut tile_map: Vec<Vec<Tile>> = input_lines.iter().enumerate()
-            .map(|(row, x)| x.chars().enumerate()
-            .map(|(col, y)| Tile::new(y, row, col)).collect::<Vec<Tile>>()).collect::<Vec<Vec<Tile>>>();
-
-        return Self{tiles: tile_map};
-    }
+    pub fn new(input_lines: &[String]) -> Result<Self, AocError> {
+        let tiles = Grid::parse(input_lines, |x| Ok(Tile::from(x)))?;
+        if tiles.rows() == 0 || tiles.cols() == 0 {
+            return Err(AocError::parse(0, "input is empty"));
+        }
 
-    fn get_tile(&self, row: usize, col: usize) -> Tile {
-        return self.tiles[row][col].clone();
+        Ok(Self { tiles })
     }
 
     fn row_count(&self) -> usize {
-        return self.tiles.len();
+        self.tiles.rows()
     }
 
     fn col_count(&self) -> usize {
-        return self.tiles[0].len();
+        self.tiles.cols()
     }
 
-    fn activate_tile(&mut self, target_tile: &Tile, direction: Direction) -> Vec<Direction> {
-        return self.tiles[target_tile.row][target_tile.col].activate(direction);
+    fn activate_tile(&mut self, position: Position, direction: Direction) -> Vec<Direction> {
+        self.tiles[position].activate(direction)
     }
 
-    fn count_activated(&self) -> usize {
-        let mut sum: usize = 0;
-        for row in &self.tiles {
-            sum += row.iter().filter(|x| x.activated).collect::<Vec<&Tile>>().len();
-        }
-        return sum;
+    pub fn count_activated(&self) -> usize {
+        self.tiles.iter().filter(|x| x.activated).count()
     }
 }
 
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut tile_map: TileMap = TileMap::new(input_lines);
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Parsed = TileMap;
+    type Answer = usize;
 
-    let max_row: usize = tile_map.row_count();
-    let max_col: usize = tile_map.col_count();
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        TileMap::new(&input_lines)
+    }
+
+    fn part_1(tile_map: &Self::Parsed) -> Self::Answer {
+        get_lava_count(tile_map.clone(), 0, 0, Direction::Right)
+    }
+
+    fn part_2(tile_map: &Self::Parsed) -> Self::Answer {
+        let max_row: usize = tile_map.row_count();
+        let max_col: usize = tile_map.col_count();
 
-    if (!part_2) {
-        return get_lava_count(tile_map.clone(), 0, 0, Direction::Left)
-    } else {
         let mut all_counts: Vec<usize> = Vec::new();
         for row in 0..max_row {
-            all_counts.push(get_lava_count(tile_map.clone(), row, 0, Direction::Left));
-            all_counts.push(get_lava_count(tile_map.clone(), row, max_col - 1, Direction::Right));
+            all_counts.push(get_lava_count(tile_map.clone(), row, 0, Direction::Right));
+            all_counts.push(get_lava_count(tile_map.clone(), row, max_col - 1, Direction::Left));
         }
-        println!("{:?}", all_counts);
-        
+        debug!("Counts from the left and right edges: {:?}", all_counts);
+
         for col in 0..max_col {
             all_counts.push(get_lava_count(tile_map.clone(), 0, col, Direction::Down));
             all_counts.push(get_lava_count(tile_map.clone(), max_row - 1, col, Direction::Up));
         }
-        println!("{:?}", all_counts);
+        debug!("Counts from every edge: {:?}", all_counts);
 
-        return *all_counts.iter().max().unwrap();
+        *all_counts.iter().max().unwrap()
     }
-
 }
 
-fn get_lava_count(mut tile_map: TileMap, start_row: usize, start_col: usize, start_direction: Direction) -> usize {
-    let max_row: i32 = tile_map.row_count() as i32;
-    let max_col: i32 = tile_map.col_count() as i32;
-
-    let mut leading_tiles: Vec<Tile> = Vec::new();
-    let mut leading_tiles_directions: Vec<Direction> = Vec::new();
-
-    leading_tiles.push(tile_map.get_tile(start_row, start_col));
-    leading_tiles_directions.push(start_direction);
-
-    while (leading_tiles.len() > 0) {
-        let mut new_leading_tiles: Vec<Tile> = Vec::new();
-        let mut new_leading_tiles_directions: Vec<Direction> = Vec::new();
-        
-        for i in 0..leading_tiles.len() {
-            let tile: &Tile = &leading_tiles[i];
-            let new_directions: Vec<Direction> = tile_map.activate_tile(tile, leading_tiles_directions[i].clone());
-
-            for d in new_directions {
-                let mut new_tile_row: i32 = tile.row as i32;
-                let mut new_tile_col: i32 = tile.col as i32;
-
-                match d {
-                    Direction::Left => new_tile_col += 1,
-                    Direction::Up => new_tile_row -= 1,
-                    Direction::Down => new_tile_row += 1,
-                    Direction::Right => new_tile_col -= 1, 
-                }
+pub fn get_lava_count(mut tile_map: TileMap, start_row: usize, start_col: usize, start_direction: Direction) -> usize {
+    let mut leading_tiles: Vec<(Position, Direction)> = vec![(Position::new(start_row, start_col), start_direction)];
+
+    while !leading_tiles.is_empty() {
+        let mut new_leading_tiles: Vec<(Position, Direction)> = Vec::new();
 
-                if new_tile_row < 0 || new_tile_col < 0 || new_tile_row >= max_row || new_tile_col >= max_col {
-                    continue;
-                } else {
-                    new_leading_tiles.push(tile_map.get_tile(new_tile_row as usize, new_tile_col as usize));
-                    new_leading_tiles_directions.push(d);
+        for (position, direction) in leading_tiles {
+            for d in tile_map.activate_tile(position, direction) {
+                if let Some(new_position) = tile_map.tiles.step(position, d) {
+                    new_leading_tiles.push((new_position, d));
                 }
             }
         }
         leading_tiles = new_leading_tiles;
-        leading_tiles_directions = new_leading_tiles_directions;
     }
 
-    return tile_map.count_activated();
+    tile_map.count_activated()
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
         // Do a quick test here
     }
 
+    #[test]
+    fn beam_directions() {
+        let mut tile = Tile::from('/');
+        assert_eq!(tile.activate(Direction::Right), vec![Direction::Up]);
+        assert_eq!(tile.activate(Direction::Right), vec![]);
+        assert_eq!(Tile::from('\\').activate(Direction::Right), vec![Direction::Down]);
+        assert_eq!(Tile::from('|').activate(Direction::Left), vec![Direction::Up, Direction::Down]);
+
+        // The beam is turned down the middle column, then split along the bottom row.
+        let tile_map = TileMap::new(&[".\\.".to_string(), "...".to_string(), ".-.".to_string()]).unwrap();
+        assert_eq!(get_lava_count(tile_map, 0, 0, Direction::Right), 6);
+    }
+
+    #[test]
+    fn empty_grid() {
+        assert_eq!(Day16::parse(Vec::new()).err(), Some(AocError::parse(0, "input is empty")));
+        assert_eq!(Day16::parse(vec![String::new()]).err(), Some(AocError::parse(0, "input is empty")));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day16>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 46);
+        answers::assert_answer(16, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day16>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 7477);
+        answers::assert_answer(16, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day16>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 51);
+        answers::assert_answer(16, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day16>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 7853);
+        answers::assert_answer(16, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
@@ -1,173 +1,192 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_17";
 
-use crate::generic;
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+use crate::error::AocError;
+use crate::generic::direction::{Direction, Position};
+use crate::generic::pathfinding::{dijkstra, Route};
+use crate::generic::{Grid, Solution};
+use crate::debug;
+
+/// How far a crucible has to go in a straight line before it can turn or stop, and how far it can
+/// go before it has to turn.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RunLimits {
+    pub min: usize,
+    pub max: usize,
 }
 
-#[derive(Clone, Debug)]
-struct Point {
-    row: usize,
-    col: usize,
-    value: usize,
-    next_point: Vec<usize>,
-    next_point_set: bool,
-    path: Vec<Direction>,
+impl RunLimits {
+    pub const CRUCIBLE: Self = Self { min: 1, max: 3 };
+    pub const ULTRA_CRUCIBLE: Self = Self { min: 4, max: 10 };
 }
 
-impl Point {
-    fn new(row: usize, col: usize, value: char) -> Self {
-        return Self{
-            row: row,
-            col: col,
-            value: value.to_digit(10).expect("Input is not a digit") as usize,
-            next_point: vec![0,0],
-            next_point_set: false,
-            path: Vec::new(),
-        }
+/// Where the crucible is, which way it is heading and how many blocks it has moved that way.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Crucible {
+    pub position: Position,
+    pub heading: Option<Direction>,
+    pub run: usize,
+}
+
+impl Crucible {
+    /// The crucibles one block on, with the heat lost entering that block. They can't turn back.
+    fn moves(&self, heat_map: &Grid<usize>, limits: RunLimits) -> Vec<(Self, usize)> {
+        Direction::CARDINALS.iter()
+            .filter(|x| match self.heading {
+                None => true,
+                Some(heading) if heading == **x => self.run < limits.max,
+                Some(heading) => heading.opposite() != **x && self.run >= limits.min,
+            })
+            .filter_map(|x| {
+                let position = heat_map.step(self.position, *x)?;
+                let run = if self.heading == Some(*x) { self.run + 1 } else { 1 };
+                Some((Self { position, heading: Some(*x), run }, heat_map[position]))
+            })
+            .collect()
     }
 }
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut points_map: Vec<Vec<Point>> = input_lines.iter().enumerate().map(|(row, x)| x.chars().enumerate().map(|(col, y)| Point::new(row, col, y)).collect::<Vec<Point>>()).collect::<Vec<Vec<Point>>>();
+fn heat_loss(value: char) -> Result<usize, AocError> {
+    value.to_digit(10).map(|x| x as usize).ok_or(AocError::parse(0, format!("heat loss '{}' is not a digit", value)))
+}
+
+/// The route losing the least heat moving a crucible from the top left to the bottom right.
+///
+/// The crucible has to have gone at least `limits.min` blocks in a straight line to stop at the end,
+/// unless the end is where it starts and it doesn't need to move at all.
+///
+/// # Panics
+///
+/// If the heat map is empty, or too small for the crucible to ever stop at the end.
+pub fn find_route(heat_map: &Grid<usize>, limits: RunLimits) -> Route<Crucible> {
+    assert!(heat_map.rows() > 0 && heat_map.cols() > 0, "the heat map is empty");
+    let start = Crucible { position: Position::new(0, 0), heading: None, run: 0 };
+    let end = Position::new(heat_map.rows() - 1, heat_map.cols() - 1);
+
+    // Only the start has no heading.
+    let route = dijkstra(start, |x| x.moves(heat_map, limits), |x| x.position == end && (x.run >= limits.min || x.heading.is_none()))
+        .expect("No route to the end");
+    debug!("Route losing {} heat:\n{}", route.cost, draw_route(heat_map, &route));
+
+    route
+}
 
-    for i in 0..1 {
-        calc_distances_djikstra(&mut points_map);
+/// The heat map with an arrow on each block the route enters, showing the way it moves.
+pub fn draw_route(heat_map: &Grid<usize>, route: &Route<Crucible>) -> String {
+    let mut drawing: Grid<char> = heat_map.map(|x| char::from_digit(*x as u32, 10).unwrap_or('?'));
+    for crucible in route.path.iter() {
+        if let Some(heading) = crucible.heading {
+            drawing[crucible.position] = match heading {
+                Direction::Up => '^',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                _ => '>',
+            };
+        }
     }
 
-    println!("{:?}", points_map[points_map.len() - 1][points_map[0].len() - 1].path);
-    return get_distance_to_start(points_map.len() - 1, points_map[0].len() - 1, &points_map);
+    drawing.to_string()
 }
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Parsed = Grid<usize>;
+    type Answer = usize;
 
-fn calc_distances_djikstra(points_map: &mut Vec<Vec<Point>>) {
-    println!("Starting Djikstra");
-    points_map[0][0].next_point_set = true;
-    for row_index in 0..points_map.len() {
-        println!("Doing row {}", row_index);
-        for col_index in 0..points_map[0].len() {
-            println!("Doing col {}", col_index);
-            if (row_index == (points_map.len() - 1) && col_index == (points_map[0].len() - 1)) {
-                // Dont do for the last one.
-                continue;
-            }
-
-            let mut neighbours: Vec<Vec<i32>> = Vec::new();
-            let mut neighbours_directions: Vec<Direction> = Vec::new();
-
-            if row_index > 0 {
-                neighbours.push(vec![(row_index as i32) - 1, col_index as i32]);
-                neighbours_directions.push(Direction::Up);
-            }
-            if row_index < points_map.len() - 1 {
-                neighbours.push(vec![(row_index as i32) + 1, col_index as i32]);
-                neighbours_directions.push(Direction::Down);
-            }
-            if col_index > 0 {
-                neighbours.push(vec![row_index as i32, (col_index as i32) - 1]);
-                neighbours_directions.push(Direction::Left);
-            }
-            if col_index < points_map[0].len() - 1 {
-                neighbours.push(vec![row_index as i32, (col_index as i32) + 1]);
-                neighbours_directions.push(Direction::Right);
-            }
-
-            let current_point = points_map[row_index][col_index].clone();
-            println!("Current point = {:?}", current_point);
-            println!("Neighbours = {:?}", neighbours);
-
-            if !current_point.next_point_set {
-                // current_point cannot connect to origin. No point telling neighbours.
-                continue;
-            }
-
-            for (i, neighbour) in neighbours.iter().enumerate() {
-                let current_point_distance = get_distance_to_start(current_point.row, current_point.col, &points_map);
-                let neighbour_distance = get_distance_to_start(neighbour[0] as usize, neighbour[1] as usize, &points_map);
-                let mut neighbour_point = &mut points_map[neighbour[0] as usize][neighbour[1] as usize];
-                let neighbour_direction = neighbours_directions[i].clone();
-
-                let path_len: usize = current_point.path.len();
-                if path_len >= 3 {
-                    if neighbour_direction == current_point.path[path_len - 1] 
-                        && neighbour_direction == current_point.path[path_len - 2]
-                        && neighbour_direction == current_point.path[path_len - 3] {
-                        // If the current direction matches the last two directions, then that would be 3 in a row.
-                        continue;
-                    }
-                }
-
-                if !neighbour_point.next_point_set {
-                    neighbour_point.next_point = vec![current_point.row, current_point.col];
-                    neighbour_point.next_point_set = true;
-                    neighbour_point.path = current_point.path.clone();
-                    neighbour_point.path.push(neighbour_direction)
-
-                } else {
-                    if current_point_distance + neighbour_point.value < neighbour_distance {
-                        neighbour_point.next_point = vec![current_point.row, current_point.col];
-                        neighbour_point.path = current_point.path.clone();
-                        neighbour_point.path.push(neighbour_direction)
-                    }
-                }
-            }
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let heat_map = Grid::parse(&input_lines, heat_loss)?;
+        if heat_map.rows() == 0 || heat_map.cols() == 0 {
+            return Err(AocError::parse(0, "input is empty"));
         }
+
+        Ok(heat_map)
     }
-}
 
-fn get_distance_to_start(point_row: usize, point_col: usize, points_map: &Vec<Vec<Point>>) -> usize {
-    let mut distance = 0;
-    let mut current_point: Point = points_map[point_row][point_col].clone();
-    while !(current_point.col == 0 && current_point.row == 0) {
-        distance += current_point.value;
-        current_point = points_map[current_point.next_point[0]][current_point.next_point[1]].clone();
+    fn part_1(heat_map: &Self::Parsed) -> Self::Answer {
+        find_route(heat_map, RunLimits::CRUCIBLE).cost
     }
 
-    return distance;
+    fn part_2(heat_map: &Self::Parsed) -> Self::Answer {
+        find_route(heat_map, RunLimits::ULTRA_CRUCIBLE).cost
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
-    fn quick_test() {
-        // Do a quick test here
+    fn route_path() {
+        let input_lines: Vec<String> = generic::read_in_file(&(INPUTS_FOLDER.to_owned() + "/input_example_2.txt")).unwrap();
+        let heat_map = Day17::parse(input_lines).unwrap();
+        let route = find_route(&heat_map, RunLimits::ULTRA_CRUCIBLE);
+
+        assert_eq!(route.path.iter().skip(1).map(|x| heat_map[x.position]).sum::<usize>(), route.cost);
+        assert!(route.path.last().is_some_and(|x| x.run >= 4));
+        assert!(route.path.windows(2).all(|x| x[0].position.manhattan_distance(&x[1].position) == 1));
+        assert_eq!(draw_route(&heat_map, &route).lines().next(), Some("1>>>>>>>1111"));
+    }
+
+    #[test]
+    fn bad_points() {
+        assert_eq!(heat_loss('7'), Ok(7));
+        assert_eq!(heat_loss('x'), Err(AocError::parse(0, "heat loss 'x' is not a digit")));
+
+        let error = Day17::parse(vec!["2413".to_string(), "32 5".to_string()]).err();
+        assert_eq!(error.map(|e| e.to_string()), Some("2:3: heat loss ' ' is not a digit".to_string()));
+        assert_eq!(Day17::parse(Vec::new()).err(), Some(AocError::parse(0, "input is empty")));
+    }
+
+    #[test]
+    fn single_block() {
+        // The crucible starts at the end, so doesn't lose any heat.
+        let heat_map = Day17::parse(vec!["7".to_string()]).unwrap();
+        for limits in [RunLimits::CRUCIBLE, RunLimits::ULTRA_CRUCIBLE] {
+            let route = find_route(&heat_map, limits);
+            assert_eq!((route.cost, route.path.len()), (0, 1));
+        }
     }
 
     #[test]
     fn example_1() {
         println!("Here we go");
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day17>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 78);
+        answers::assert_answer(17, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day17>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 21138);
+        answers::assert_answer(17, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day17>(&input_filename, true);
+        println!("Answer = {:?}", answer);
+        answers::assert_answer(17, 2, &input_filename, answer);
+    }
+
+    #[test]
+    fn example_2_part_2() {
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_2.txt";
+        let answer = generic::solve::<Day17>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 30);
+        answers::assert_answer(17, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day17>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 7185540);
+        answers::assert_answer(17, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
@@ -1,74 +1,93 @@
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::Solution;
+use crate::{debug, trace};
 use std::collections::HashMap;
 
-fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
-    let input_lines = generic::read_in_file(input_filename);
+/// The largest number of cubes of each colour shown at once during a game.
+pub type Game = HashMap<String, usize>;
+
+/// Parse `Game <id>: <count> <colour>, ...; ...`, keeping the most of each colour.
+pub fn game_from_string(input_string: &str) -> Result<Game, AocError> {
+    let start = input_string.find(": ")
+        .ok_or_else(|| AocError::parse(1, format!("expected 'Game <number>: <cubes>', found '{}'", input_string)))? + 2;
+
+    let mut game: Game = HashMap::new();
+    let mut column: usize = start + 1;
+    for game_turn in input_string[start..].split("; ") {
+        for colour_string in game_turn.split(", ") {
+            let (count_string, colour_name) = colour_string.split_once(' ')
+                .ok_or_else(|| AocError::parse(column, format!("expected '<count> <colour>', found '{}'", colour_string)))?;
+            let count = count_string.parse::<usize>()
+                .map_err(|_| AocError::parse(column, format!("'{}' is not a number", count_string)))?;
+
+            let most = game.entry(colour_name.to_string()).or_default();
+            *most = (*most).max(count);
+            // Turns and colours are both split by two characters.
+            column += colour_string.len() + 2;
+        }
+    }
 
-    let mut games: Vec<HashMap<String, usize>> = Vec::new();
+    Ok(game)
+}
 
-    for mut line in input_lines.clone() {
-        //println!("{:?}", line);
-        let mut new_game: HashMap<String, usize> = HashMap::new();
-        line = line[line.find(":").unwrap()+2..].to_string();
+pub struct Day2;
 
-        for game_turn in line.split("; ") {
-            for colour_string in game_turn.split(", ") {
-                let count = colour_string[..colour_string.find(" ").unwrap()].parse::<usize>().unwrap();
-                let colour_name = colour_string[colour_string.find(" ").unwrap() + 1..].to_string();
-                if new_game.contains_key(&colour_name) {
-                    if count > *new_game.get(&colour_name).unwrap() {
-                        new_game.insert(colour_name, count);
-                    }
-                } else {
-                    new_game.insert(colour_name, count);
-                }
-            }
+impl Solution for Day2 {
+    type Parsed = Vec<Game>;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let mut games: Vec<Game> = Vec::new();
+
+        for (i, line) in input_lines.iter().enumerate() {
+            let new_game: Game = game_from_string(line).map_err(|e| e.at_line(i + 1))?;
+            trace!("{:?}", new_game);
+            games.push(new_game);
         }
-        //println!("{:?}", new_game);
-        games.push(new_game);
+
+        Ok(games)
     }
 
-    let mut target_cubes: HashMap<String, usize> = HashMap::new();
-    target_cubes.insert("red".to_string(), 12);
-    target_cubes.insert("green".to_string(), 13);
-    target_cubes.insert("blue".to_string(), 14);
+    fn part_1(games: &Self::Parsed) -> Self::Answer {
+        let mut target_cubes: HashMap<String, usize> = HashMap::new();
+        target_cubes.insert("red".to_string(), 12);
+        target_cubes.insert("green".to_string(), 13);
+        target_cubes.insert("blue".to_string(), 14);
 
-    let mut possible_games: Vec<usize> = Vec::new();
+        let mut possible_games: Vec<usize> = Vec::new();
+
+        for (i, game) in games.iter().enumerate() {
+            let mut possible = true;
+            for colour in game.keys() {
+                if target_cubes.contains_key(colour) {
+                    possible = possible & (game.get(colour).unwrap() <= target_cubes.get(colour).unwrap());
+                } else {
+                    possible = false;
+                }
+            }
 
-    for (i, game) in games.iter().enumerate() {
-        let mut possible = true;
-        for colour in game.keys() {
-            if target_cubes.contains_key(colour) {
-                possible = possible & (game.get(colour).unwrap() <= target_cubes.get(colour).unwrap());
-            } else {
-                possible = false;
+            if possible {
+                possible_games.push(i+1);
             }
-        }
 
-        if possible {
-            possible_games.push(i+1);
+            trace!("Game {} {:?}: possible = {}", i + 1, game, possible);
         }
 
-        println!("{:?}", input_lines[i]);
-        println!("{:?}", game);
-        println!("Possible = {:?}", possible);
-    }
-
-    println!("{:?}", possible_games);
+        debug!("Possible games = {:?}", possible_games);
 
+        possible_games.iter().sum::<usize>()
+    }
 
-    if part_2 {
+    fn part_2(games: &Self::Parsed) -> Self::Answer {
         let mut game_powers: Vec<usize> = Vec::new();
         for game in games {
-            println!("{:?}", game);
-
             let game_power: usize = game.get("red").unwrap() * game.get("blue").unwrap() * game.get("green").unwrap();
+            trace!("{:?} has power {}", game, game_power);
             game_powers.push(game_power);
         }
-        return game_powers.iter().sum();
-    } else {
-        return possible_games.iter().sum::<usize>();
+
+        game_powers.iter().sum()
     }
 }
 
@@ -76,32 +95,49 @@ fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
+
+    #[test]
+    fn bad_games() {
+        let game = game_from_string("Game 1: 3 blue, 4 red; 1 red, 6 blue").unwrap();
+        assert_eq!((game["blue"], game["red"]), (6, 4));
+        assert_eq!(game_from_string("Game 1 3 blue").err(), Some(AocError::parse(1, "expected 'Game <number>: <cubes>', found 'Game 1 3 blue'")));
+        assert_eq!(game_from_string("Game 1: 3 blue; x red").err(), Some(AocError::parse(17, "'x' is not a number")));
+        assert_eq!(game_from_string("Game 1: 3 blue, red").err(), Some(AocError::parse(17, "expected '<count> <colour>', found 'red'")));
+
+        let input_lines: Vec<String> = vec!["Game 1: 3 blue".to_string(), "Game 2 3 blue".to_string()];
+        assert_eq!(Day2::parse(input_lines).err().map(|e| e.to_string()), Some("2:1: expected 'Game <number>: <cubes>', found 'Game 2 3 blue'".to_string()));
+    }
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle("src/inputs/day_2/input_example_1.txt", false);
+        let input_filename = "src/inputs/day_2/input_example_1.txt";
+        let answer = generic::solve::<Day2>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 8);
+        answers::assert_answer(2, 1, input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle("src/inputs/day_2/input.txt", false);
+        let input_filename = "src/inputs/day_2/input.txt";
+        let answer = generic::solve::<Day2>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 1931);
+        answers::assert_answer(2, 1, input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle("src/inputs/day_2/input_example_1.txt", true);
+        let input_filename = "src/inputs/day_2/input_example_1.txt";
+        let answer = generic::solve::<Day2>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 2286);
+        answers::assert_answer(2, 2, input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle("src/inputs/day_2/input.txt", true);
+        let input_filename = "src/inputs/day_2/input.txt";
+        let answer = generic::solve::<Day2>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 83105);
+        answers::assert_answer(2, 2, input_filename, answer);
     }
 }
\ No newline at end of file
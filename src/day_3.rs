@@ -1,91 +1,83 @@
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::{Grid, Solution};
+use crate::debug;
 
 #[derive(Debug, Clone)]
-struct Number {
-    value: usize,
-    row: usize,
-    left: usize,
-    right: usize,
+pub struct Number {
+    pub value: usize,
+    pub row: usize,
+    pub left: usize,
+    pub right: usize,
 }
 
 #[derive(Debug, Clone)]
-struct Gear {
-    row: usize,
-    col: usize,
-    numbers: Vec<Number>,
+pub struct Gear {
+    pub row: usize,
+    pub col: usize,
+    pub numbers: Vec<Number>,
 }
 
-fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
-    let input_lines = generic::read_in_file(input_filename);
+pub struct Day3;
 
-    //let mut games: Vec<HashMap<String, usize>> = Vec::new();
+impl Solution for Day3 {
+    type Parsed = Grid<char>;
+    type Answer = usize;
 
-    let char_map: Vec<Vec<char>> = get_char_map(&input_lines);
-    let all_numbers: Vec<Number> = get_all_numbers(&char_map);
-    let part_numbers: Vec<Number> = all_numbers.iter().cloned().filter(|x| has_symbol_next_to_number(&char_map, &*x)).collect();
-    let mut asterisks: Vec<Gear> = get_all_asterisks(&char_map);
-
-    let mut all_numbers_by_row: Vec<Vec<Number>> = Vec::new();
-    for row in 0..char_map.len() {
-        all_numbers_by_row.push(all_numbers.iter().cloned().filter(|x| x.row == row).collect());
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        Grid::parse(&input_lines, Ok)
     }
 
-    for asterisk in asterisks.iter_mut() {
-        if asterisk.row > 0 {
-            asterisk.numbers.append(&mut all_numbers_by_row[asterisk.row - 1].iter().cloned().filter(|x| is_number_neighbour(x, asterisk.row - 1, asterisk.col)).collect());
-        }
-        asterisk.numbers.append(&mut all_numbers_by_row[asterisk.row].iter().cloned().filter(|x| is_number_neighbour(x, asterisk.row, asterisk.col)).collect());
-        if asterisk.row < all_numbers_by_row.len() {
-            asterisk.numbers.append(&mut all_numbers_by_row[asterisk.row + 1].iter().cloned().filter(|x| is_number_neighbour(x, asterisk.row + 1, asterisk.col)).collect());
-        }
+    fn part_1(char_map: &Self::Parsed) -> Self::Answer {
+        let all_numbers: Vec<Number> = get_all_numbers(char_map);
+        let part_numbers: Vec<Number> = all_numbers.iter().filter(|x| has_symbol_next_to_number(char_map, x)).cloned().collect();
+
+        part_numbers.iter().map(|x| x.value).sum()
     }
 
-    println!("{:?}", asterisks);
+    fn part_2(char_map: &Self::Parsed) -> Self::Answer {
+        let all_numbers: Vec<Number> = get_all_numbers(char_map);
+        let mut asterisks: Vec<Gear> = get_all_asterisks(char_map);
 
-    if !part_2 {
-        return part_numbers.iter().map(|x| x.value).sum();
-    } else {
-        return asterisks.iter().filter(|x| x.numbers.len() == 2).map(|x| x.numbers[0].value * x.numbers[1].value).sum();
-    }
-}
+        let mut all_numbers_by_row: Vec<Vec<Number>> = Vec::new();
+        for row in 0..char_map.rows() {
+            all_numbers_by_row.push(all_numbers.iter().cloned().filter(|x| x.row == row).collect());
+        }
+
+        for asterisk in asterisks.iter_mut() {
+            let last_row = (asterisk.row + 1).min(char_map.rows() - 1);
+            for row_numbers in &all_numbers_by_row[asterisk.row.saturating_sub(1)..=last_row] {
+                asterisk.numbers.extend(row_numbers.iter().filter(|x| is_number_neighbour(x, asterisk.row, asterisk.col)).cloned());
+            }
+        }
 
-fn get_char_map(input_lines: &Vec<String>) -> Vec<Vec<char>> {
-    let mut char_map: Vec<Vec<char>> = Vec::new();
+        debug!("{:?}", asterisks);
 
-    char_map.push(vec!['.'; input_lines[0].len()+2]);
-    for line in input_lines {
-        let mut new_line: Vec<char> = Vec::new();
-        new_line.push('.');
-        new_line.append(&mut line.chars().collect());
-        new_line.push('.');
-        char_map.push(new_line);
+        asterisks.iter().filter(|x| x.numbers.len() == 2).map(|x| x.numbers[0].value * x.numbers[1].value).sum()
     }
-    char_map.push(vec!['.'; input_lines[0].len()+2]);
-
-    return char_map;
 }
 
-fn get_all_numbers(char_map: &Vec<Vec<char>>) -> Vec<Number> {
+pub fn get_all_numbers(char_map: &Grid<char>) -> Vec<Number> {
     let mut all_numbers: Vec<Number> = Vec::new();
 
-    for row in 0..char_map.len() {
+    for (row, row_chars) in char_map.iter_rows().enumerate() {
         let mut number_string: String = String::new();
         let mut left = 0;
-        for col in 0..char_map[0].len() {
-            if char_map[row][col].is_numeric() {
-                if number_string.len() == 0 {
+        // A '.' past the end of the row finishes any number running up to the edge.
+        for (col, c) in row_chars.iter().chain(std::iter::once(&'.')).enumerate() {
+            if c.is_numeric() {
+                if number_string.is_empty() {
                     // We are at the start of a number.
                     left = col;
                 }
-                number_string.push(char_map[row][col]);
+                number_string.push(*c);
             } else {
                 // did we just finish a number?
-                if number_string.len() > 0 {
+                if !number_string.is_empty() {
                     let new_number = Number{
                         value: number_string.parse::<usize>().unwrap(),
-                        row: row,
-                        left: left,
+                        row,
+                        left,
                         right: col - 1
                     };
                     all_numbers.push(new_number);
@@ -98,17 +90,8 @@ fn get_all_numbers(char_map: &Vec<Vec<char>>) -> Vec<Number> {
     return all_numbers;
 }
 
-fn get_all_asterisks(char_map: &Vec<Vec<char>>) -> Vec<Gear> {
-    let mut all_asterisks: Vec<Gear> = Vec::new();
-    for row in 0..char_map.len() {
-        for col in 0..char_map[0].len() {
-            if char_map[row][col] == '*' {
-                all_asterisks.push(Gear { row: row, col: col, numbers: Vec::new() })
-            }
-        }
-    }
-
-    return all_asterisks;
+pub fn get_all_asterisks(char_map: &Grid<char>) -> Vec<Gear> {
+    char_map.enumerate().filter(|(_, x)| **x == '*').map(|((row, col), _)| Gear { row, col, numbers: Vec::new() }).collect()
 }
 
 fn is_symbol(input_char: char) -> bool {
@@ -116,28 +99,20 @@ fn is_symbol(input_char: char) -> bool {
     return !input_char.is_numeric() && input_char != '.';
 }
 
-fn has_symbol_next_to_number(char_map: &Vec<Vec<char>>, target_number: &Number) -> bool {
-    let mut has_symbol = false;
-    //println!("Checking from Rows {} to {}", target_number.row - 1, target_number.row + 2);
-    for row in (target_number.row - 1)..(target_number.row + 2) {
-        //println!("Checking from Cols {} to {}", target_number.left - 1, target_number.right + 2);
-        for col in (target_number.left - 1)..(target_number.right + 2) {
-            has_symbol = has_symbol || is_symbol(char_map[row][col]);
-        }
-    }
-
-    return has_symbol;
+fn has_symbol_next_to_number(char_map: &Grid<char>, target_number: &Number) -> bool {
+    (target_number.left..(target_number.right + 1))
+        .any(|col| char_map.neighbours_8((target_number.row, col)).any(|x| is_symbol(char_map[x])))
 }
 
 fn is_number_neighbour(target_number: &Number, row: usize, col: usize) -> bool {
-    return (target_number.row >= (row - 1)) && (target_number.row <= (row + 1))
-        && (target_number.left <= col + 1) && (target_number.right >= col - 1);
+    target_number.row.abs_diff(row) <= 1 && target_number.left <= col + 1 && target_number.right + 1 >= col
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
@@ -152,29 +127,33 @@ mod tests {
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle("src/inputs/day_3/input_example_1.txt", false);
+        let input_filename = "src/inputs/day_3/input_example_1.txt";
+        let answer = generic::solve::<Day3>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 4361);
+        answers::assert_answer(3, 1, input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle("src/inputs/day_3/input.txt", false);
+        let input_filename = "src/inputs/day_3/input.txt";
+        let answer = generic::solve::<Day3>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 556367);
+        answers::assert_answer(3, 1, input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle("src/inputs/day_3/input_example_1.txt", true);
+        let input_filename = "src/inputs/day_3/input_example_1.txt";
+        let answer = generic::solve::<Day3>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 467835);
+        answers::assert_answer(3, 2, input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle("src/inputs/day_3/input.txt", true);
+        let input_filename = "src/inputs/day_3/input.txt";
+        let answer = generic::solve::<Day3>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 89471771);
+        answers::assert_answer(3, 2, input_filename, answer);
     }
 }
\ No newline at end of file
@@ -1,17 +1,38 @@
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::{self, Solution};
+use crate::{debug, trace};
 use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
-struct Card {
-    index: usize,
-    winning_numbers: HashSet<usize>,
-    numbers: HashSet<usize>,
-    copies: usize,
+pub struct Card {
+    pub index: usize,
+    pub winning_numbers: HashSet<usize>,
+    pub numbers: HashSet<usize>,
+    pub copies: usize,
 }
 
 impl Card {
-    fn get_winning_matches(&self) -> usize {
+    /// Parse `Card <index>: <winning numbers> | <numbers>`.
+    pub fn from_string(input_string: &str) -> Result<Self, AocError> {
+        let (label, all_numbers) = input_string.split_once(':')
+            .ok_or_else(|| AocError::parse(1, format!("expected 'Card <number>: <numbers> | <numbers>', found '{}'", input_string)))?;
+        let index = label.strip_prefix("Card").and_then(|x| x.trim().parse::<usize>().ok())
+            .ok_or_else(|| AocError::parse(1, format!("'{}' is not a card number", label)))?;
+        let (winning_string, numbers_string) = all_numbers.split_once('|')
+            .ok_or_else(|| AocError::parse(input_string.len() + 1, "expected '|' between the winning numbers and the numbers"))?;
+
+        let winning_start = label.len() + 1;
+        let numbers_start = winning_start + winning_string.len() + 1;
+        Ok(Self {
+            index,
+            winning_numbers: generic::parse_numbers(winning_string, ' ').map_err(|e| e.offset_column(winning_start))?.into_iter().collect(),
+            numbers: generic::parse_numbers(numbers_string, ' ').map_err(|e| e.offset_column(numbers_start))?.into_iter().collect(),
+            copies: 1,
+        })
+    }
+
+    pub fn get_winning_matches(&self) -> usize {
         let mut winning_numbers_count: usize = 0;
         for num in &self.numbers {
             if self.winning_numbers.contains(&num) {
@@ -22,7 +43,7 @@ impl Card {
         return winning_numbers_count;
     }
 
-    fn get_worth(&self) -> usize {
+    pub fn get_worth(&self) -> usize {
         let winning_numbers_count: usize = self.get_winning_matches();
         if winning_numbers_count > 0 {
             return usize::pow(2,(winning_numbers_count - 1) as u32);
@@ -32,86 +53,99 @@ impl Card {
     }
 }
 
-fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
-    let input_lines = generic::read_in_file(input_filename);
-    let mut all_cards: Vec<Card> = get_all_cards(&input_lines);
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Card>;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        get_all_cards(&input_lines)
+    }
+
+    fn part_1(all_cards: &Self::Parsed) -> Self::Answer {
+        all_cards.iter().map(|x| x.get_worth()).sum()
+    }
+
+    fn part_2(all_cards: &Self::Parsed) -> Self::Answer {
+        let mut all_cards: Vec<Card> = all_cards.clone();
 
-    for i in 0..all_cards.len() {
-        for j in 0..all_cards[i].get_winning_matches() {
-            for _k in 0..all_cards[i].copies {
-                if (j + i) < (all_cards.len() - 1) {
-                    all_cards[j + i + 1].copies += 1;
+        for i in 0..all_cards.len() {
+            for j in 0..all_cards[i].get_winning_matches() {
+                for _k in 0..all_cards[i].copies {
+                    if (j + i) < (all_cards.len() - 1) {
+                        all_cards[j + i + 1].copies += 1;
+                    }
                 }
             }
+            trace!("Card {} has {} winners", all_cards[i].index, all_cards[i].get_winning_matches());
         }
-        //println!("Card {} has {} winners", all_cards[i].index, all_cards[i].get_winning_matches());
-        //println!("{:?}", all_cards.iter().map(|x| x.copies).collect::<Vec<usize>>());
-    }
 
-    for card in &all_cards {
-        println!("card {:?} has {} copies", card.index, card.copies);
-    }
+        for card in &all_cards {
+            debug!("card {:?} has {} copies", card.index, card.copies);
+        }
 
-    if !part_2 {
-        return all_cards.iter().map(|x| x.get_worth()).sum();
-    } else {
-        return all_cards.iter().map(|x| x.copies).sum();
+        all_cards.iter().map(|x| x.copies).sum()
     }
 }
 
-fn get_all_cards(input_lines: &Vec<String>) -> Vec<Card> {
-    let mut all_cards: Vec<Card> = Vec::new();
-
-    for line in input_lines {
-        let all_numbers = line[line.find(":").unwrap() + 1..].to_string();
-        let winning_numbers_string: &str = all_numbers.split("|").collect::<Vec<&str>>()[0].trim();
-        let numbers_string: &str = all_numbers.split("|").collect::<Vec<&str>>()[1].trim();
-        
-        all_cards.push(Card { 
-            index: line["Card ".len()..line.find(":").unwrap()].trim().parse::<usize>().unwrap(),
-            winning_numbers: HashSet::from_iter(winning_numbers_string.split(" ").filter(|x| !x.is_empty()).map(|x| x.parse::<usize>().unwrap())), 
-            numbers: HashSet::from_iter(numbers_string.split(" ").filter(|x| !x.is_empty()).map(|x| x.parse::<usize>().unwrap())),
-            copies: 1,
-        });
-    }
-
-    return all_cards;
+pub fn get_all_cards(input_lines: &[String]) -> Result<Vec<Card>, AocError> {
+    input_lines.iter().enumerate().map(|(i, x)| Card::from_string(x).map_err(|e| e.at_line(i + 1))).collect()
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
     }
 
+    #[test]
+    fn bad_cards() {
+        let card = Card::from_string("Card   1: 41 48 | 83  86 48").unwrap();
+        assert_eq!((card.index, card.get_winning_matches()), (1, 1));
+        assert_eq!(Card::from_string("Card 1 41 48 | 83").err(), Some(AocError::parse(1, "expected 'Card <number>: <numbers> | <numbers>', found 'Card 1 41 48 | 83'")));
+        assert_eq!(Card::from_string("Card x: 41 | 83").err(), Some(AocError::parse(1, "'Card x' is not a card number")));
+        assert_eq!(Card::from_string("Card 1: 41 48 83").err(), Some(AocError::parse(17, "expected '|' between the winning numbers and the numbers")));
+        assert_eq!(Card::from_string("Card 1: 41 4x | 83").err(), Some(AocError::parse(12, "'4x' is not a number")));
+        assert_eq!(Card::from_string("Card 1: 41 | 83  8-").err(), Some(AocError::parse(18, "'8-' is not a number")));
+
+        let input_lines: Vec<String> = vec!["Card 1: 41 | 83".to_string(), "Card 2: 41 | x".to_string()];
+        assert_eq!(Day4::parse(input_lines).err().map(|e| e.to_string()), Some("2:14: 'x' is not a number".to_string()));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle("src/inputs/day_4/input_example_1.txt", false);
+        let input_filename = "src/inputs/day_4/input_example_1.txt";
+        let answer = generic::solve::<Day4>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 13);
+        answers::assert_answer(4, 1, input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle("src/inputs/day_4/input.txt", false);
+        let input_filename = "src/inputs/day_4/input.txt";
+        let answer = generic::solve::<Day4>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 21138);
+        answers::assert_answer(4, 1, input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle("src/inputs/day_4/input_example_1.txt", true);
+        let input_filename = "src/inputs/day_4/input_example_1.txt";
+        let answer = generic::solve::<Day4>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 30);
+        answers::assert_answer(4, 2, input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle("src/inputs/day_4/input.txt", true);
+        let input_filename = "src/inputs/day_4/input.txt";
+        let answer = generic::solve::<Day4>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 7185540);
+        answers::assert_answer(4, 2, input_filename, answer);
     }
 }
\ No newline at end of file
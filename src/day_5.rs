@@ -1,9 +1,14 @@
 #[warn(dead_code)]
-use crate::generic;
-use std::collections::HashMap;
-
+use crate::error::AocError;
+use crate::generic::interval::IntervalSet;
+use crate::generic::{self, Solution};
+use crate::{debug, trace};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// What the numbers in the almanac count. The puzzle's own categories have names here, and any
+/// others are kept as they are written.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-enum Category {
+pub enum Category {
     Seed,
     Soil,
     Fertilizer,
@@ -12,11 +17,12 @@ enum Category {
     Temperature,
     Humidity,
     Location,
+    Other(String),
 }
 
 impl Category {
-    fn from_string(input_string: &str) -> Self {
-        return match input_string {
+    pub fn from_string(input_string: &str) -> Self {
+        match input_string {
             "seed" => Self::Seed,
             "soil" => Self::Soil,
             "fertilizer" => Self::Fertilizer,
@@ -25,43 +31,65 @@ impl Category {
             "temperature" => Self::Temperature,
             "humidity" => Self::Humidity,
             "location" => Self::Location,
-            _ => Self::Seed,
+            _ => Self::Other(input_string.to_string()),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-struct SeedRange {
-    start: usize,
-    length: usize,
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Seed => "seed",
+            Self::Soil => "soil",
+            Self::Fertilizer => "fertilizer",
+            Self::Water => "water",
+            Self::Light => "light",
+            Self::Temperature => "temperature",
+            Self::Humidity => "humidity",
+            Self::Location => "location",
+            Self::Other(name) => name,
+        };
+        write!(f, "{}", name)
+    }
 }
 
 #[derive(Clone, Debug)]
-struct Range {
-    src_start: usize,
-    dest_start: usize,
-    length: usize,
+pub struct Range {
+    pub src_start: usize,
+    pub dest_start: usize,
+    pub length: usize,
 }
 
 impl Range {
-    fn from_string(input_string: &str) -> Self {
-        let inputs: Vec<usize> = input_string.split(" ").map(|x| x.parse::<usize>().unwrap()).collect();
-        return Self { src_start: inputs[1], dest_start: inputs[0], length: inputs[2] };
+    /// Parse `<dest start> <src start> <length>`.
+    pub fn from_string(input_string: &str) -> Result<Self, AocError> {
+        let mut inputs: Vec<usize> = Vec::new();
+        let mut column: usize = 1;
+        for number_string in input_string.split(' ') {
+            let number = number_string.parse::<usize>()
+                .map_err(|_| AocError::parse(column, format!("'{}' is not a number", number_string)))?;
+            inputs.push(number);
+            column += number_string.len() + 1;
+        }
+
+        if inputs.len() != 3 {
+            return Err(AocError::parse(1, format!("expected 3 numbers in a range, found {}", inputs.len())));
+        }
+
+        Ok(Self { src_start: inputs[1], dest_start: inputs[0], length: inputs[2] })
     }
 }
 
 #[derive(Clone, Debug)]
-struct Map {
-    src_category: Category,
-    dst_category: Category,
-    ranges: Vec<Range>,
+pub struct Map {
+    pub src_category: Category,
+    pub dst_category: Category,
+    pub ranges: Vec<Range>,
 }
 
 impl Map {
-    fn src_to_dst(&self, src_number: usize) -> usize {
-        //println!("Checking src_number {}", src_number);
+    pub fn src_to_dst(&self, src_number: usize) -> usize {
         for range in &self.ranges {
-            //println!("\t{:?}", range);
             if src_number >= range.src_start && src_number < (range.src_start + range.length) {
                 return range.dest_start + (src_number - range.src_start);
             }
@@ -70,207 +98,372 @@ impl Map {
         return src_number;
     }
 
-    fn src_range_to_dst(&self, src_range: &SeedRange) -> Vec<SeedRange> {
-        let mut out_ranges: Vec<SeedRange> = Vec::new();
-        let mut current_ranges: Vec<SeedRange> = vec![src_range.clone()];
+    /// Map a whole set of source numbers at once.
+    ///
+    /// Each range moves the part of the set it covers, and whatever no range covers is left as
+    /// it is, the same as [`Map::src_to_dst`] does for a single number.
+    pub fn src_range_to_dst(&self, src_ranges: &IntervalSet) -> IntervalSet {
+        let mut unmapped: IntervalSet = src_ranges.clone();
+        let mut mapped: IntervalSet = IntervalSet::new();
 
-        println!("Doing src_range_to_dst");
-        println!("\tseed_ranges = {:?}", current_ranges);
-        println!("\tMap ranges = {:?}", &self.ranges);
+        trace!("{:?} to {:?}: mapping {:?} through {:?}", self.src_category, self.dst_category, src_ranges, &self.ranges);
 
         for range in &self.ranges {
-            let mut new_current_ranges: Vec<SeedRange> = Vec::new();
-
-            for seed_range in current_ranges {
-                println!("\t\tseed_range = {:?}", seed_range);
-                println!("\t\trange = {:?}", range);
-                if seed_range.start < range.src_start {
-                    if (seed_range.start + seed_range.length) < range.src_start {
-                        // No overlap.
-                        new_current_ranges.push(seed_range);
-                    } else {
-                        // Some overlap
-                        let start_range: SeedRange = SeedRange{
-                            start: seed_range.start,
-                            length: range.src_start - seed_range.start
-                        };
-                        new_current_ranges.push(start_range);
-
-                        if (range.src_start + range.length) > (seed_range.start + seed_range.length) {
-                            // end in the middle of the range
-                            let mid_range: SeedRange = SeedRange {
-                                start: range.dest_start,
-                                length: (seed_range.start + seed_range.length) - range.src_start
-                            };
-                            out_ranges.push(mid_range);
-                        } else {
-                            // seed range ends after
-                            let mid_range: SeedRange = SeedRange {
-                                start: range.dest_start,
-                                length: range.length
-                            };
-                            let end_range: SeedRange = SeedRange {
-                                start: range.src_start + range.length,
-                                length: (seed_range.start + seed_range.length) - (range.src_start + range.length)
-                            };
-
-                            out_ranges.push(mid_range);
-                            new_current_ranges.push(end_range);
-                        }
-                    }
-                } else if seed_range.start < (range.src_start + range.length) {
-                    // seed range starts in the middle of range
-                    if (seed_range.start + seed_range.length) <= (range.src_start + range.length) {
-                        // finished inside the range
-                        let mid_range: SeedRange = SeedRange {
-                            start: range.dest_start + (seed_range.start - range.src_start),
-                            length: seed_range.length
-                        };
-                        out_ranges.push(mid_range);
-                    } else {
-                        // finishes outside the range
-                        let mid_range: SeedRange = SeedRange {
-                            start: range.dest_start + (seed_range.start - range.src_start),
-                            length: (range.src_start + range.length) - seed_range.start
-                        };
-                        let end_range: SeedRange = SeedRange {
-                            start: range.src_start + range.length,
-                            length: (seed_range.start + seed_range.length) - (range.src_start + range.length)
-                        };
-
-                        out_ranges.push(mid_range);
-                        new_current_ranges.push(end_range);
-                    }
-                } else {
-                    // seed range starts after the range has finished
-                    new_current_ranges.push(seed_range);
-                }
+            let src_start = range.src_start as i64;
+            let covered = IntervalSet::from(src_start..(src_start + range.length as i64));
+            let offset = range.dest_start as i64 - src_start;
+
+            mapped = mapped.union(&unmapped.intersection(&covered).shift(offset));
+            unmapped = unmapped.difference(&covered);
+        }
+
+        let out_ranges = mapped.union(&unmapped);
+        trace!("\tout_ranges = {:?}", out_ranges);
+
+        out_ranges
+    }
+}
+
+/// A whole chain of maps collapsed into one.
+///
+/// The numbers from 0 up are split into pieces, and every number in a piece moves by the same
+/// offset. Pieces are sorted by where they start, so looking one up is a binary search.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiecewiseMap {
+    /// `(start, offset)` for each piece. A piece runs up to the next one's start, and the first
+    /// starts at 0. Neighbouring pieces always have different offsets.
+    pieces: Vec<(i64, i64)>,
+}
+
+impl PiecewiseMap {
+    /// The map that leaves every number where it is.
+    pub fn identity() -> Self {
+        Self { pieces: vec![(0, 0)] }
+    }
+
+    pub fn from_map(map: &Map) -> Self {
+        let mut starts: Vec<i64> = vec![0];
+        for range in &map.ranges {
+            starts.push(range.src_start as i64);
+            starts.push((range.src_start + range.length) as i64);
+        }
+        starts.sort();
+        starts.dedup();
+
+        // The first range covering a piece wins, as in `Map::src_to_dst`.
+        Self::from_pieces(starts.into_iter().map(|start| {
+            let offset = map.ranges.iter()
+                .find(|x| x.src_start as i64 <= start && start < (x.src_start + x.length) as i64)
+                .map_or(0, |x| x.dest_start as i64 - x.src_start as i64);
+            (start, offset)
+        }))
+    }
+
+    fn from_pieces(pieces: impl IntoIterator<Item = (i64, i64)>) -> Self {
+        let mut merged: Vec<(i64, i64)> = Vec::new();
+        for (start, offset) in pieces {
+            if merged.last().is_none_or(|x| x.1 != offset) {
+                merged.push((start, offset));
             }
+        }
+
+        Self { pieces: merged }
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = (std::ops::Range<i64>, i64)> + '_ {
+        self.pieces.iter().enumerate().map(|(index, (start, offset))| (*start..self.piece_end(index), *offset))
+    }
 
-            current_ranges = new_current_ranges.clone();
+    fn piece_end(&self, index: usize) -> i64 {
+        self.pieces.get(index + 1).map_or(i64::MAX, |x| x.0)
+    }
+
+    fn piece_index(&self, number: i64) -> usize {
+        self.pieces.partition_point(|x| x.0 <= number).saturating_sub(1)
+    }
+
+    pub fn get(&self, number: usize) -> usize {
+        let number = number as i64;
+        (number + self.pieces[self.piece_index(number)].1) as usize
+    }
+
+    /// Where every number in `numbers` ends up.
+    pub fn get_ranges(&self, numbers: &IntervalSet) -> IntervalSet {
+        let mut out_ranges: Vec<std::ops::Range<i64>> = Vec::new();
+        for range in numbers.ranges() {
+            let mut index = self.piece_index(range.start);
+            let mut start = range.start;
+            while start < range.end {
+                let end = range.end.min(self.piece_end(index));
+                let offset = self.pieces[index].1;
+                out_ranges.push((start + offset)..(end + offset));
+                start = end;
+                index += 1;
+            }
         }
 
-        // Any ranges not covered are just turned into the dest as is.
-        out_ranges.append(&mut current_ranges);
-        
-        out_ranges.sort_by_key(|x| x.start);
-        println!("\tout_ranges = {:?}", out_ranges);
+        IntervalSet::from_ranges(out_ranges)
+    }
 
-        let in_length: usize = src_range.length;
-        let out_length: usize = out_ranges.iter().map(|x| x.length).sum();
+    /// Every number that ends up in `targets`. Unlike the forward lookups this checks each piece.
+    pub fn inverse_ranges(&self, targets: &IntervalSet) -> IntervalSet {
+        self.pieces()
+            .flat_map(|(range, offset)| {
+                let image = IntervalSet::from((range.start + offset)..(range.end.saturating_add(offset)));
+                image.intersection(targets).shift(-offset).ranges().to_vec()
+            })
+            .collect()
+    }
 
-        println!("\tin_length = {}, out_length = {}", in_length, out_length);
-        assert_eq!(in_length, out_length);
-        
+    /// This map followed by `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces: Vec<(i64, i64)> = Vec::new();
+        for (range, offset) in self.pieces() {
+            // Split the piece wherever its image crosses into a new piece of `next`.
+            let mut start = range.start;
+            let mut index = next.piece_index(start + offset);
+            while start < range.end {
+                pieces.push((start, offset + next.pieces[index].1));
+                start = match next.piece_end(index) {
+                    i64::MAX => range.end,
+                    end => range.end.min(end - offset),
+                };
+                index += 1;
+            }
+        }
 
-        return out_ranges;
+        Self::from_pieces(pieces)
     }
 }
 
-struct Almanac {
-    seeds: Vec<usize>,
-    maps_by_src: HashMap<Category, Map>,
+impl std::fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (range, offset) in self.pieces() {
+            match range.end {
+                i64::MAX => writeln!(f, "{}.. {:+}", range.start, offset)?,
+                end => writeln!(f, "{}..{} {:+}", range.start, end, offset)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The seeds and every map between categories.
+///
+/// The maps form a directed graph from source to destination category. Parsing checks that it
+/// has no cycles, that no two maps join the same pair of categories, and that no map has ranges
+/// whose sources overlap.
+pub struct Almanac {
+    pub seeds: Vec<usize>,
+    pub maps_by_src: HashMap<Category, Vec<Map>>,
 }
 
 impl Almanac {
-    fn from_lines(input_lines: &Vec<String>) -> Self {
-        let seeds: Vec<usize> = input_lines[0][(input_lines[0].find(":").unwrap() + 1)..].trim().split(" ").map(|x| x.parse::<usize>().unwrap()).collect();
-        let mut all_maps: HashMap<Category, Map> = HashMap::new();
+    pub fn from_lines(input_lines: &[String]) -> Result<Self, AocError> {
+        let seeds_line = input_lines.first().ok_or_else(|| AocError::parse(1, "expected 'seeds: <numbers>', found an empty input").at_line(1))?;
+        let seeds: Vec<usize> = Self::parse_seeds(seeds_line).map_err(|e| e.at_line(1))?;
+        let mut all_maps: HashMap<Category, Vec<Map>> = HashMap::new();
+        // The line each map's header is on, for pointing at maps in errors.
+        let mut header_lines: HashMap<(Category, Category), usize> = HashMap::new();
 
         let mut line_index = 2;
         while line_index < input_lines.len() {
-            let mut current_line: String = input_lines[line_index].clone();
-            let src_string: String = current_line[..current_line.find("-").unwrap()].to_string();
-            let dst_string: String = current_line[(current_line.find("-to-").unwrap()+4)..current_line.find(" ").unwrap()].to_string();
+            let (src_category, dst_category) = Self::parse_header(&input_lines[line_index]).map_err(|e| e.at_line(line_index + 1))?;
+            let key = (src_category.clone(), dst_category.clone());
+            if let Some(previous_line) = header_lines.get(&key) {
+                return Err(AocError::parse(1, format!("there is already a {}-to-{} map on line {}", src_category, dst_category, previous_line))
+                    .at_line(line_index + 1));
+            }
+            header_lines.insert(key, line_index + 1);
             let mut new_map = Map{
-                src_category: Category::from_string(src_string.as_str()),
-                dst_category: Category::from_string(dst_string.as_str()),
+                src_category,
+                dst_category,
                 ranges: Vec::new(),
             };
+            let mut range_lines: Vec<usize> = Vec::new();
 
             // Get all ranges
             line_index += 1;
-            current_line = input_lines[line_index].clone();
-            while current_line != "" {
-                new_map.ranges.push(Range::from_string(&current_line));
-                line_index += 1;
-                if line_index == input_lines.len() {
-                    break;
+            while line_index < input_lines.len() && !input_lines[line_index].is_empty() {
+                let range = Range::from_string(&input_lines[line_index]).map_err(|e| e.at_line(line_index + 1))?;
+                let src_end = range.src_start + range.length;
+                if let Some((_, other_line)) = new_map.ranges.iter().zip(range_lines.iter())
+                    .find(|(x, _)| x.src_start < src_end && range.src_start < x.src_start + x.length) {
+                    return Err(AocError::parse(1, format!("source range {}..{} overlaps the one on line {}", range.src_start, src_end, other_line))
+                        .at_line(line_index + 1));
                 }
-                current_line = input_lines[line_index].clone();
+
+                new_map.ranges.push(range);
+                range_lines.push(line_index + 1);
+                line_index += 1;
             }
 
-            all_maps.insert(new_map.src_category.clone(), new_map);
+            all_maps.entry(new_map.src_category.clone()).or_default().push(new_map);
             line_index += 1;
         }
 
-
-        return Self {
-            seeds: seeds,
+        let almanac = Self {
+            seeds,
             maps_by_src: all_maps,
+        };
+
+        if let Some(cycle) = almanac.find_cycle() {
+            let names: Vec<String> = cycle.iter().map(|x| x.to_string()).collect();
+            let last_map = (cycle[cycle.len() - 2].clone(), cycle[cycle.len() - 1].clone());
+            return Err(AocError::parse(1, format!("the maps go round in a cycle: {}", names.join(" -> ")))
+                .at_line(header_lines[&last_map]));
         }
+
+        Ok(almanac)
     }
-}
 
+    /// Parse `seeds: <numbers>`.
+    fn parse_seeds(input_string: &str) -> Result<Vec<usize>, AocError> {
+        let colon = input_string.find(':')
+            .ok_or_else(|| AocError::parse(1, format!("expected 'seeds: <numbers>', found '{}'", input_string)))?;
+
+        generic::parse_numbers(&input_string[(colon + 1)..], ' ').map_err(|e| e.offset_column(colon + 1))
+    }
 
+    /// Parse `<src>-to-<dst> map:`.
+    fn parse_header(input_string: &str) -> Result<(Category, Category), AocError> {
+        let (src_string, dst_string) = input_string.strip_suffix(" map:")
+            .and_then(|x| x.split_once("-to-"))
+            .ok_or_else(|| AocError::parse(1, format!("expected '<source>-to-<destination> map:', found '{}'", input_string)))?;
 
+        Ok((Category::from_string(src_string), Category::from_string(dst_string)))
+    }
 
-fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
-    let input_lines = generic::read_in_file(input_filename);
-    let almanac: Almanac = Almanac::from_lines(&input_lines);
-    
-    let mut seed_ranges: Vec<SeedRange> = Vec::new();
-    for i in (0..almanac.seeds.len()).step_by(2) {
-        seed_ranges.push(SeedRange { start: almanac.seeds[i], length: almanac.seeds[i+1] })
+    /// A cycle of categories, starting and ending with the same one, if the maps have one.
+    fn find_cycle(&self) -> Option<Vec<Category>> {
+        let mut finished: HashSet<&Category> = HashSet::new();
+        let mut sources: Vec<&Category> = self.maps_by_src.keys().collect();
+        // Only so the same cycle is reported every time.
+        sources.sort_by_key(|x| x.to_string());
+
+        for start in sources {
+            let mut path: Vec<&Category> = Vec::new();
+            if let Some(cycle) = self.find_cycle_from(start, &mut path, &mut finished) {
+                return Some(cycle);
+            }
+        }
+
+        None
     }
 
+    fn find_cycle_from<'a>(&'a self, category: &'a Category, path: &mut Vec<&'a Category>, finished: &mut HashSet<&'a Category>) -> Option<Vec<Category>> {
+        if let Some(index) = path.iter().position(|x| *x == category) {
+            let mut cycle: Vec<Category> = path[index..].iter().map(|x| (*x).clone()).collect();
+            cycle.push(category.clone());
+            return Some(cycle);
+        }
+        if finished.contains(category) {
+            return None;
+        }
+
+        path.push(category);
+        for map in self.maps_by_src.get(category).into_iter().flatten() {
+            if let Some(cycle) = self.find_cycle_from(&map.dst_category, path, finished) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        finished.insert(category);
 
-    let mut all_locations: Vec<usize> = Vec::new();
+        None
+    }
 
-    if !part_2 {
-        // PART 1
-        for seed in almanac.seeds {
-            let mut current_category: Category = Category::Seed;
-            let mut current_number: usize = seed;
-            let mut indent: String = "".to_string();
-            while almanac.maps_by_src.contains_key(&current_category) {
-                //println!("{}{:?} {:?}", indent, current_category, current_number);
-                current_number = almanac.maps_by_src[&current_category].src_to_dst(current_number);
-                current_category = almanac.maps_by_src[&current_category].dst_category.clone();
-                indent.push('\t');
+    /// The fewest maps that take `src` numbers to `dst` numbers, in the order to apply them.
+    pub fn path(&self, src: &Category, dst: &Category) -> Result<Vec<&Map>, AocError> {
+        // Breadth first, remembering the map used to first reach each category.
+        let mut reached_by: HashMap<&Category, Option<&Map>> = HashMap::from([(src, None)]);
+        let mut queue: VecDeque<&Category> = VecDeque::from([src]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == dst {
+                let mut path: Vec<&Map> = Vec::new();
+                let mut current_category = dst;
+                while let Some(map) = reached_by[current_category] {
+                    path.push(map);
+                    current_category = &map.src_category;
+                }
+                path.reverse();
+                return Ok(path);
             }
-            //println!("{}{:?} {:?}", indent, current_category, current_number);
-            //println!("Seed {:?} becomes {:?} {:?}", seed, almanac.maps_by_src[&Category::seed].src_to_dst(seed), almanac.maps_by_src[&Category::seed].dst_category)
 
-            all_locations.push(current_number);
+            for map in self.maps_by_src.get(category).into_iter().flatten() {
+                if !reached_by.contains_key(&map.dst_category) {
+                    reached_by.insert(&map.dst_category, Some(map));
+                    queue.push_back(&map.dst_category);
+                }
+            }
         }
 
-        return *all_locations.iter().min().unwrap();
-    } else {
-        // PART 2
-        let mut current_ranges: Vec<SeedRange>;
-        let mut current_category: Category = Category::Seed;
+        Err(AocError::parse(0, format!("no maps lead from {} to {}", src, dst)))
+    }
 
-        while almanac.maps_by_src.contains_key(&current_category) {
-            current_ranges = seed_ranges.clone();
-            let mut new_ranges: Vec<SeedRange> = Vec::new();
+    /// Collapse the maps from `src` through to `dst` into one.
+    pub fn compose(&self, src: &Category, dst: &Category) -> Result<PiecewiseMap, AocError> {
+        Ok(self.path(src, dst)?.into_iter()
+            .fold(PiecewiseMap::identity(), |composed, map| composed.then(&PiecewiseMap::from_map(map))))
+    }
+}
 
-            println!("current_ranges {:?}", current_ranges);
-            
 
-            for seed_range in current_ranges {
-                new_ranges.append(&mut almanac.maps_by_src[&current_category].src_range_to_dst(&seed_range));
-            }
 
-            println!("new_ranges {:?}", new_ranges);
-            current_category = almanac.maps_by_src[&current_category].dst_category.clone();
-            seed_ranges = new_ranges.clone();
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = Almanac;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let almanac = Almanac::from_lines(&input_lines)?;
+        almanac.path(&Category::Seed, &Category::Location)?;
+
+        // Part 2 reads the seeds as pairs of start and length, and both parts need at least one.
+        if almanac.seeds.is_empty() {
+            return Err(AocError::parse(1, "there are no seeds").at_line(1));
+        }
+        if almanac.seeds.len() % 2 != 0 {
+            return Err(AocError::parse(1, format!("seeds come in pairs of start and length, but there are {}", almanac.seeds.len())).at_line(1));
+        }
+        if almanac.seeds.chunks(2).all(|x| x[1] == 0) {
+            return Err(AocError::parse(1, "every range of seeds is empty").at_line(1));
+        }
+
+        Ok(almanac)
+    }
+
+    fn part_1(almanac: &Self::Parsed) -> Self::Answer {
+        let maps = almanac.path(&Category::Seed, &Category::Location).expect("checked when parsing");
+        let mut all_locations: Vec<usize> = Vec::new();
+
+        for seed in almanac.seeds.iter() {
+            let location: usize = maps.iter().fold(*seed, |number, map| map.src_to_dst(number));
+            debug!("Seed {} becomes location {}", seed, location);
+
+            all_locations.push(location);
+        }
+
+        *all_locations.iter().min().unwrap()
+    }
+
+    fn part_2(almanac: &Self::Parsed) -> Self::Answer {
+        let mut current_ranges: IntervalSet = almanac.seeds.chunks(2)
+            .map(|x| (x[0] as i64)..((x[0] + x[1]) as i64))
+            .collect();
+
+        for map in almanac.path(&Category::Seed, &Category::Location).expect("checked when parsing") {
+            debug!("{} ranges {:?}", map.src_category, current_ranges);
+            current_ranges = map.src_range_to_dst(&current_ranges);
         }
 
-        println!("{:?}", seed_ranges);
-        println!("end category = {:?}", current_category);
+        debug!("location ranges {:?}", current_ranges);
 
-        return seed_ranges.iter().map(|x| x.start).min().expect("oh no not a number");
+        current_ranges.min().expect("oh no not a number") as usize
     }
 }
 
@@ -278,31 +471,91 @@ fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
         // Do a quick test here
     }
 
+    #[test]
+    fn bad_ranges() {
+        assert_eq!(Range::from_string("50 98 2").map(|x| x.src_start), Ok(98));
+        assert_eq!(Range::from_string("50 9x8 2").err(), Some(AocError::parse(4, "'9x8' is not a number")));
+        assert_eq!(Range::from_string("50 98").err(), Some(AocError::parse(1, "expected 3 numbers in a range, found 2")));
+
+        let input_lines: Vec<String> = ["seeds: 79 14", "", "seed-to-soil map:", "50 98 2", "52 50"].iter().map(|x| x.to_string()).collect();
+        let error = Almanac::from_lines(&input_lines).err();
+        assert_eq!(error.map(|e| e.to_string()), Some("5:1: expected 3 numbers in a range, found 2".to_string()));
+
+        let error = |x: &[&str]| Almanac::from_lines(&x.iter().map(|x| x.to_string()).collect::<Vec<String>>()).err().map(|e| e.to_string());
+        assert_eq!(error(&[]), Some("1:1: expected 'seeds: <numbers>', found an empty input".to_string()));
+        assert_eq!(error(&["seeds: 79 x14"]), Some("1:11: 'x14' is not a number".to_string()));
+        assert_eq!(error(&["seeds:  79  x14"]), Some("1:13: 'x14' is not a number".to_string()));
+        assert_eq!(error(&["seeds 79 14"]), Some("1:1: expected 'seeds: <numbers>', found 'seeds 79 14'".to_string()));
+    }
+
+    #[test]
+    fn category_graphs() {
+        let lines = |x: &[&str]| x.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+
+        // Categories with any names, branching from seed.
+        let almanac = Almanac::from_lines(&lines(&[
+            "seeds: 1 2", "",
+            "seed-to-soil map:", "10 0 5", "",
+            "seed-to-sky map:", "100 0 50", "",
+            "sky-to-star dust map:", "0 100 10",
+        ])).unwrap();
+        let star_dust = Category::from_string("star dust");
+        assert_eq!(star_dust, Category::Other("star dust".to_string()));
+        assert_eq!(almanac.path(&Category::Seed, &star_dust).unwrap().len(), 2);
+        assert_eq!(almanac.compose(&Category::Seed, &star_dust).unwrap().get(3), 3);
+        assert_eq!(almanac.compose(&Category::Seed, &star_dust).unwrap().get(30), 130);
+        assert_eq!(almanac.compose(&Category::Seed, &Category::Soil).unwrap().get(3), 13);
+        assert_eq!(almanac.path(&Category::Soil, &star_dust).err(), Some(AocError::parse(0, "no maps lead from soil to star dust")));
+
+        let error = |x: &[&str]| Almanac::from_lines(&lines(x)).err().map(|e| e.to_string());
+        assert_eq!(error(&["seeds: 1", "", "seed-to-soil map:", "10 0 5", "", "soil-to-seed map:", "0 10 5"]),
+            Some("6:1: the maps go round in a cycle: seed -> soil -> seed".to_string()));
+        assert_eq!(error(&["seeds: 1", "", "seed-to-soil map:", "10 0 5", "", "seed-to-soil map:", "0 10 5"]),
+            Some("6:1: there is already a seed-to-soil map on line 3".to_string()));
+        assert_eq!(error(&["seeds: 1", "", "seed-to-soil map:", "10 0 5", "20 4 5"]),
+            Some("5:1: source range 4..9 overlaps the one on line 4".to_string()));
+        assert_eq!(error(&["seeds: 1", "", "seed to soil:"]),
+            Some("3:1: expected '<source>-to-<destination> map:', found 'seed to soil:'".to_string()));
+
+        // The puzzle itself needs a way from seeds to locations, and seeds for both parts.
+        let error = Day5::parse(lines(&["seeds: 1", "", "seed-to-soil map:", "10 0 5"])).err();
+        assert_eq!(error, Some(AocError::parse(0, "no maps lead from seed to location")));
+        let error = |seeds: &str| Day5::parse(lines(&[seeds, "", "seed-to-location map:", "10 0 5"])).err().map(|e| e.to_string());
+        assert_eq!(error("seeds: 1 2"), None);
+        assert_eq!(error("seeds:"), Some("1:1: there are no seeds".to_string()));
+        assert_eq!(error("seeds: 1 2 3"), Some("1:1: seeds come in pairs of start and length, but there are 3".to_string()));
+        assert_eq!(error("seeds: 1 0 7 0"), Some("1:1: every range of seeds is empty".to_string()));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle("src/inputs/day_5/input_example_1.txt", false);
+        let input_filename = "src/inputs/day_5/input_example_1.txt";
+        let answer = generic::solve::<Day5>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 35);
+        answers::assert_answer(5, 1, input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle("src/inputs/day_5/input.txt", false);
+        let input_filename = "src/inputs/day_5/input.txt";
+        let answer = generic::solve::<Day5>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 324724204);
+        answers::assert_answer(5, 1, input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle("src/inputs/day_5/input_example_1.txt", true);
+        let input_filename = "src/inputs/day_5/input_example_1.txt";
+        let answer = generic::solve::<Day5>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 46);
+        answers::assert_answer(5, 2, input_filename, answer);
     }
 
     #[test]
@@ -314,84 +567,70 @@ mod tests {
             ranges: test_ranges
         };
 
-        // 1 Start before. End before
-        let seed_range: SeedRange = SeedRange { start: 0, length: 5 };
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(seed_range, out_ranges[0]);
-
-        // 2 Start before. End middle
-        let seed_range: SeedRange = SeedRange { start: 0, length: 25 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange{start: 0, length: 20}, SeedRange{start: 40, length: 5}
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
-        
-        // 3 Start before. End after
-        let seed_range: SeedRange = SeedRange { start: 0, length: 50 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 0, length: 20 },
-            SeedRange { start: 40, length: 20 },
-            SeedRange { start: 40, length: 10 }
+        let cases = [
+            // 1 Start before. End before
+            (0..5, IntervalSet::from(0..5)),
+            // 2 Start before. End middle
+            (0..25, IntervalSet::from_ranges([0..20, 40..45])),
+            // 3 Start before. End after. The unmapped 40..50 overlaps the mapped 40..60.
+            (0..50, IntervalSet::from_ranges([0..20, 40..60])),
+            // 4 Start middle. End middle
+            (25..35, IntervalSet::from(45..55)),
+            // 5 Start middle. End after
+            (25..50, IntervalSet::from(40..60)),
+            // 6 Start after. End after
+            (45..50, IntervalSet::from(45..50)),
+            // 7 Start exactly. End middle
+            (20..30, IntervalSet::from(40..50)),
+            // 8 Start exactly. End exactly
+            (20..40, IntervalSet::from(40..60)),
+            // 9 Start middle. End exactly
+            (30..40, IntervalSet::from(50..60)),
         ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
 
-        // 4 Start middle. End middle
-        let seed_range: SeedRange = SeedRange { start: 25, length: 10 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 45, length: 10 }
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
-
-        // 5 Start middle. End after
-        let seed_range: SeedRange = SeedRange { start: 25, length: 25 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 40, length: 10 },
-            SeedRange { start: 45, length: 15 }
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
+        for (seed_range, expected_ranges) in cases {
+            let out_ranges: IntervalSet = test_map.src_range_to_dst(&IntervalSet::from(seed_range.clone()));
+            assert_eq!(out_ranges, expected_ranges, "mapping {:?}", seed_range);
+        }
 
-        // 6 Start after. End after
-        let seed_range: SeedRange = SeedRange { start: 45, length: 5 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 45, length: 5 }
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
+        // Several seed ranges are mapped together.
+        let seed_ranges = IntervalSet::from_ranges([0..5, 30..45]);
+        assert_eq!(test_map.src_range_to_dst(&seed_ranges).ranges(), &[0..5, 40..45, 50..60]);
+    }
 
-        // 7 Start exactly. End middle
-        let seed_range: SeedRange = SeedRange { start: 20, length: 10 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 40, length: 10 }
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
+    #[test]
+    fn compose_maps() {
+        let input_lines = generic::read_in_file("src/inputs/day_5/input_example_1.txt").unwrap();
+        let almanac = Almanac::from_lines(&input_lines).unwrap();
+        let seed_to_location = almanac.compose(&Category::Seed, &Category::Location).unwrap();
+
+        let maps = almanac.path(&Category::Seed, &Category::Location).unwrap();
+        assert_eq!(maps.len(), 7);
+        for seed in 0..150 {
+            let location: usize = maps.iter().fold(seed, |number, map| map.src_to_dst(number));
+            assert_eq!(seed_to_location.get(seed), location, "seed {}", seed);
+        }
 
-        // 8 Start exactly. End exactly
-        let seed_range: SeedRange = SeedRange { start: 20, length: 20 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 40, length: 20 }
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
+        let seed_ranges = IntervalSet::from_ranges([79..93, 55..68]);
+        assert_eq!(seed_to_location.get_ranges(&seed_ranges).min(), Some(46));
+        assert_eq!(seed_to_location.inverse_ranges(&IntervalSet::from(46..47)).intersection(&seed_ranges), IntervalSet::from(82..83));
+        for location in 0..150 {
+            let seeds = seed_to_location.inverse_ranges(&IntervalSet::from(location..(location + 1)));
+            assert!(seeds.ranges().iter().flat_map(|x| x.clone()).all(|x| seed_to_location.get(x as usize) == location as usize));
+        }
 
-        // 8 Start middle. End exactly
-        let seed_range: SeedRange = SeedRange { start: 30, length: 10 };
-        let expected_ranges: Vec<SeedRange> = vec![
-            SeedRange { start: 50, length: 10 }
-        ];
-        let out_ranges: Vec<SeedRange> = test_map.src_range_to_dst(&seed_range);
-        assert_eq!(out_ranges, expected_ranges);
+        let soil_to_water = almanac.compose(&Category::Soil, &Category::Water).unwrap();
+        assert_eq!(soil_to_water.get(81), 81);
+        assert_eq!(almanac.compose(&Category::Water, &Category::Soil).err(), Some(AocError::parse(0, "no maps lead from water to soil")));
+        assert_eq!(almanac.compose(&Category::Light, &Category::Light), Ok(PiecewiseMap::identity()));
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle("src/inputs/day_5/input.txt", true);
+        let input_filename = "src/inputs/day_5/input.txt";
+        let answer = generic::solve::<Day5>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 104070862);
+        answers::assert_answer(5, 2, input_filename, answer);
 
         //9284340 too low
     }
@@ -1,143 +1,251 @@
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::{self, math, Solution};
+use crate::{debug, trace};
+use std::ops::RangeInclusive;
 
 
+/// A race, in `u128` so the single long race in part 2 still fits.
 #[derive(Clone, Debug, Eq, PartialEq)]
-struct Race {
-    time: usize,
-    distance: usize,
+pub struct Race {
+    pub time: u128,
+    pub distance: u128,
 }
 
 impl Race {
-    fn get_times(&self) -> Vec<usize> {
+    /// The times to hold the button for that beat the record, if any do.
+    fn get_times(&self) -> Option<RangeInclusive<u128>> {
         // x is the time to press the button
         // speed = x
         // distance = (race.time - x) * speed
         //          = x*race.time - x^2
         //
-        // x^2 - x*race.time - distance = 0
+        // The distance is symmetric about race.time / 2, so the last winning time is as far
+        // from race.time as the first is from 0.
         //
-        let time: f64 = self.time as f64;
-        let distance: f64 = self.distance as f64;
-        let mut first_time: f64 = (time + (time.powi(2) - (4.0*distance)).powf(0.5)) / 2.0;
-        let mut second_time: f64 = (time - (time.powi(2) - (4.0*distance)).powf(0.5)) / 2.0;
+        let first_time = match self.time.checked_mul(self.time).is_some() {
+            true => self.first_winning_time(),
+            false => self.search_first_winning_time(),
+        }?;
+        let times = first_time..=(self.time - first_time);
+        trace!("{:?}: wins holding for {:?}", self, times);
+
+        Some(times)
+    }
 
-        println!("first = {:?}", first_time);
-        println!("second = {:?}", second_time);
+    /// Solve x^2 - x*race.time + race.distance < 0 exactly, for times that can be squared.
+    fn first_winning_time(&self) -> Option<u128> {
+        // A record too big for an i128 is further than race.time^2 / 4 < 2^126 can reach.
+        let distance = i128::try_from(self.distance).ok()?;
+        let times = math::quadratic_below_zero(1, -(self.time as i128), distance)?;
 
-        // check if exact
-        if first_time.fract() == 0.0 {
-            first_time -= 1.0;
-        }
-        if second_time.fract() == 0.0 {
-            second_time += 1.0;
+        Some(*times.start() as u128)
+    }
+
+    /// The same as [`Race::first_winning_time`] by binary search, for times too long to square.
+    fn search_first_winning_time(&self) -> Option<u128> {
+        // A product too big for a u128 is certainly further than the record.
+        let wins = |x: u128| x.checked_mul(self.time - x).is_none_or(|distance| distance > self.distance);
+        let (mut low, mut high) = (0, self.time / 2);
+        if !wins(high) {
+            return None;
         }
 
-        let first_time_rounded: usize = first_time.floor() as usize;
-        let second_time_rounded: usize = second_time.ceil() as usize;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match wins(middle) {
+                true => high = middle,
+                false => low = middle + 1,
+            }
+        }
 
-        return vec![second_time_rounded, first_time_rounded];
+        Some(low)
     }
 
-    fn get_ways_to_win(&self) -> usize {
-        let race_times: Vec<usize> = self.get_times();
-        return race_times[1] - race_times[0] + 1;
+    pub fn get_ways_to_win(&self) -> u128 {
+        self.get_times().map_or(0, |x| x.end() - x.start() + 1)
     }
 }
 
 
-fn races_from_input_lines(input_lines: Vec<String>) -> Vec<Race> {
-    let mut output_races: Vec<Race> = Vec::new();
-    let input_times: Vec<usize> = input_lines[0]
-        .split(" ")
-        .filter(|x| !x.is_empty())
-        .collect::<Vec<&str>>()[1..]
-        .iter()
-        .map(|x| x.parse::<usize>().expect(format!("Input '{}' not a number.", x).as_str()))
-        .collect();
-    let input_distances: Vec<usize> = input_lines[1]
-        .split(" ")
-        .filter(|x| !x.is_empty())
-        .collect::<Vec<&str>>()[1..]
-        .iter()
-        .map(|x| x.parse::<usize>().expect(format!("Input '{}' not a number.", x).as_str()))
-        .collect();
-
-    for i in 0..input_times.len() {
-        output_races.push(Race { time: input_times[i], distance: input_distances[i] });
-    }
-
-    return output_races;
-}
+/// Parse one row of numbers after its name, like `Time:      7  15   30`.
+fn numbers_from_string(input_string: &str, name: &str) -> Result<Vec<u128>, AocError> {
+    let numbers_string = input_string.strip_prefix(name).and_then(|x| x.strip_prefix(':'))
+        .ok_or_else(|| AocError::parse(1, format!("expected '{}: <numbers>', found '{}'", name, input_string)))?;
 
-fn solve_puzzle(input_filename: &str, part_2: bool) -> usize {
-    let input_lines = generic::read_in_file(input_filename);
-    let races: Vec<Race>;
+    generic::parse_numbers(numbers_string, ' ').map_err(|e| e.offset_column(name.len() + 1))
+}
 
-    if !part_2 {
-        // PART ONE
-        races = races_from_input_lines(input_lines);
-    } else {
-        // PART TWO
-        let time = input_lines[0].chars().filter(|x| x.is_numeric()).collect::<String>().parse::<usize>().expect("Oh no bad time");
-        let distance = input_lines[1].chars().filter(|x| x.is_numeric()).collect::<String>().parse::<usize>().expect("Oh no bad distance");
+pub fn races_from_input_lines(input_lines: &[String]) -> Result<Vec<Race>, AocError> {
+    let mut rows: Vec<Vec<u128>> = Vec::new();
+    for (line_index, name) in ["Time", "Distance"].iter().enumerate() {
+        let input_line = input_lines.get(line_index)
+            .ok_or_else(|| AocError::parse(1, format!("expected '{}: <numbers>', found the end of the input", name)).at_line(line_index + 1))?;
+        rows.push(numbers_from_string(input_line, name).map_err(|e| e.at_line(line_index + 1))?);
+    }
 
-        races = vec![Race{time: time, distance: distance}];
+    if rows[0].is_empty() {
+        return Err(AocError::parse(1, "there are no races").at_line(1));
+    }
+    if rows[0].len() != rows[1].len() {
+        return Err(AocError::parse(1, format!("there are {} times but {} distances", rows[0].len(), rows[1].len())).at_line(2));
     }
 
-    let mut ways_to_win: Vec<usize> = Vec::new();
-    let mut final_product: usize = 1;
+    Ok(rows[0].iter().zip(rows[1].iter()).map(|(time, distance)| Race { time: *time, distance: *distance }).collect())
+}
+
+/// The one race part 2 reads, ignoring the spaces between the numbers. An error is on the line of
+/// the row that makes a number too big for a `u128`.
+pub fn combine_races(races: &[Race]) -> Result<Race, AocError> {
+    let combine = |numbers: Vec<u128>, name: &str, line: usize| {
+        let digits: String = numbers.iter().map(|x| x.to_string()).collect();
+        digits.parse::<u128>().map_err(|_| AocError::parse(1, format!("the {} together make {}, which is too big", name, digits)).at_line(line))
+    };
+    let time = combine(races.iter().map(|x| x.time).collect(), "times", 1)?;
+    let distance = combine(races.iter().map(|x| x.distance).collect(), "distances", 2)?;
+
+    Ok(Race { time, distance })
+}
+
+pub fn get_product_of_wins(races: &[Race]) -> u128 {
+    let mut final_product: u128 = 1;
 
     for race in races {
         let number_of_wins = race.get_ways_to_win();
 
-        println!("{:?}", race);
-        println!("{:?}", race.get_times());
-        println!("{:?}", number_of_wins);
-        ways_to_win.push(number_of_wins);
+        debug!("{:?} can be won {} ways", race, number_of_wins);
         final_product *= number_of_wins;
     }
 
-    return final_product;
+    final_product
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Parsed = Vec<Race>;
+    type Answer = u128;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        let races = races_from_input_lines(&input_lines)?;
+        combine_races(&races)?;
+
+        Ok(races)
+    }
+
+    fn part_1(races: &Self::Parsed) -> Self::Answer {
+        get_product_of_wins(races)
+    }
+
+    fn part_2(races: &Self::Parsed) -> Self::Answer {
+        get_product_of_wins(&[combine_races(races).expect("checked when parsing")])
+    }
 }
-    
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
         // Do a quick test here
     }
 
+    #[test]
+    fn ways_to_win() {
+        assert_eq!(Race { time: 7, distance: 9 }.get_times(), Some(2..=5));
+        // Holding for 10 or 20 only equals the record.
+        assert_eq!(Race { time: 30, distance: 200 }.get_ways_to_win(), 9);
+        assert_eq!(Race { time: 4, distance: 4 }.get_times(), None);
+        assert_eq!(Race { time: 4, distance: 4 }.get_ways_to_win(), 0);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for time in 0..80u128 {
+            // Up to a little past the furthest any hold time can go.
+            for distance in 0..(time * time / 4 + 3) {
+                let race = Race { time, distance };
+                let expected = (0..=time).filter(|x| x * (time - x) > distance).count() as u128;
+                assert_eq!(race.get_ways_to_win(), expected, "{:?}", race);
+
+                let search = race.search_first_winning_time();
+                let closed_form = race.first_winning_time();
+                assert_eq!(search, closed_form, "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn long_races() {
+        // The record is more than 64 bits.
+        let race = Race { time: 71530000000, distance: 940200000000000000000 };
+        assert_eq!(race.search_first_winning_time(), race.first_winning_time());
+        assert_eq!(race.get_ways_to_win(), 71530000000 - 2 * race.get_times().unwrap().start() + 1);
+
+        // Only the search works when the time can't be squared. Half the time squared is just
+        // too big to fit as well, so it has to count as beating any record.
+        let half: u128 = 1 << 64;
+        let race = Race { time: 2 * half, distance: (half - 1) * (half + 1) };
+        assert_eq!(race.get_times(), Some(half..=half));
+        let race = Race { time: 2 * half, distance: (half - 1) * (half + 1) - 1 };
+        assert_eq!(race.get_times(), Some((half - 1)..=(half + 1)));
+        assert_eq!(Race { time: u128::MAX, distance: u128::MAX }.get_ways_to_win(), u128::MAX - 3);
+    }
+
+    #[test]
+    fn bad_races() {
+        let races = |x: &[&str]| races_from_input_lines(&x.iter().map(|x| x.to_string()).collect::<Vec<String>>());
+        assert_eq!(races(&["Time:      7  15", "Distance:  9  40"]).map(|x| x.len()), Ok(2));
+
+        let error = |x: &[&str]| races(x).err().map(|e| e.to_string());
+        assert_eq!(error(&[]), Some("1:1: expected 'Time: <numbers>', found the end of the input".to_string()));
+        assert_eq!(error(&["Time: 7"]), Some("2:1: expected 'Distance: <numbers>', found the end of the input".to_string()));
+        assert_eq!(error(&["Time:  7  1x5", "Distance: 9 40"]), Some("1:11: '1x5' is not a number".to_string()));
+        assert_eq!(error(&["Time: 7", "Distances: 9"]), Some("2:1: expected 'Distance: <numbers>', found 'Distances: 9'".to_string()));
+        assert_eq!(error(&["Time: 7 15", "Distance: 9"]), Some("2:1: there are 2 times but 1 distances".to_string()));
+        assert_eq!(error(&["Time:", "Distance:"]), Some("1:1: there are no races".to_string()));
+
+        // Part 2's race has to fit as well.
+        let parse = |x: &[&str]| Day6::parse(x.iter().map(|x| x.to_string()).collect()).err().map(|e| e.to_string());
+        let max = u128::MAX.to_string();
+        assert_eq!(parse(&[&format!("Time: {}", max), &format!("Distance: {}", max)]), None);
+        assert_eq!(parse(&[&format!("Time: {} 1", max), "Distance: 1 1"]), Some(format!("1:1: the times together make {}1, which is too big", max)));
+        assert_eq!(parse(&["Time: 1 1", &format!("Distance: 1 {}", max)]), Some(format!("2:1: the distances together make 1{}, which is too big", max)));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle("src/inputs/day_6/input_example_1.txt", false);
+        let input_filename = "src/inputs/day_6/input_example_1.txt";
+        let answer = generic::solve::<Day6>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 288);
+        answers::assert_answer(6, 1, input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle("src/inputs/day_6/input.txt", false);
+        let input_filename = "src/inputs/day_6/input.txt";
+        let answer = generic::solve::<Day6>(input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 2065338);
+        answers::assert_answer(6, 1, input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle("src/inputs/day_6/input_example_1.txt", true);
+        let input_filename = "src/inputs/day_6/input_example_1.txt";
+        let answer = generic::solve::<Day6>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 71503);
+        answers::assert_answer(6, 2, input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle("src/inputs/day_6/input.txt", true);
+        let input_filename = "src/inputs/day_6/input.txt";
+        let answer = generic::solve::<Day6>(input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 34934171);
+        answers::assert_answer(6, 2, input_filename, answer);
         
         // 34934174 is too high
     }
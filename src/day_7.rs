@@ -1,13 +1,15 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_7";
 
 #[warn(dead_code)]
-use crate::generic;
-use std::collections::HashSet;
-use std::cmp::{Ordering, PartialOrd};
+use crate::error::AocError;
+use crate::generic::Solution;
+use crate::trace;
+use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
 
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone)]
-enum HandType {
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+pub enum HandType {
     HighCard = 1,
     OnePair = 2,
     TwoPair = 3,
@@ -17,244 +19,452 @@ enum HandType {
     FiveOfAKind = 7,
 }
 
-#[derive(Debug,Eq,PartialEq, Clone)]
-struct Hand {
-    cards: Vec<char>,
-    hand_type: HandType,
-    bid: usize,
-    joker_count: usize,
-}
+impl HandType {
+    /// Every type, from worst to best.
+    pub const ALL: [Self; 7] = [Self::HighCard, Self::OnePair, Self::TwoPair, Self::ThreeOfAKind, Self::FullHouse, Self::FourOfAKind, Self::FiveOfAKind];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HighCard => "high_card",
+            Self::OnePair => "one_pair",
+            Self::TwoPair => "two_pair",
+            Self::ThreeOfAKind => "three_of_a_kind",
+            Self::FullHouse => "full_house",
+            Self::FourOfAKind => "four_of_a_kind",
+            Self::FiveOfAKind => "five_of_a_kind",
+        }
+    }
 
-impl Hand {
-    fn from_string(input_string: String) -> Self {
-        let chars_list: Vec<char> = input_string[0..input_string.find(" ").expect("No space in input line")].chars().collect();
-        let chars_set: HashSet<char> = HashSet::from_iter(chars_list.clone());
-        let bid: usize = input_string[(input_string.find(" ").expect("No space in input line") + 1)..].parse::<usize>().expect("Bid is not a number");
-
-        let hand_type: HandType;
-
-        if chars_set.len() == 1 {
-            // Five of a Kind
-            hand_type = HandType::FiveOfAKind;
-        } else if chars_set.len() == 4 {
-            // One Pair
-            hand_type = HandType::OnePair;
-        } else if chars_set.len() == 5 {
-            // High Card
-            hand_type = HandType::HighCard;
-        } else {
-            // Four of a Kind
-            // Full House
-            // Three of a Kind
-            // Two Pair
-            let mut char_occurences: Vec<usize> = Vec::new();
-            for c in chars_set {
-                char_occurences.push(chars_list.iter().filter(|x| **x == c).count());
-            }
-            char_occurences.sort();
-            char_occurences.reverse();
-
-            if char_occurences.len() == 2 {
-                if char_occurences[0] == 4 {
-                    hand_type = HandType::FourOfAKind;
-                } else {
-                    hand_type = HandType::FullHouse;
-                }
-            } else {
-                if char_occurences[0] == 3 {
-                    hand_type = HandType::ThreeOfAKind;
-                } else {
-                    hand_type = HandType::TwoPair;
-                }
-            }
+    /// The type of a hand with groups of matching cards of these sizes, biggest first.
+    ///
+    /// Hands of more than 5 cards can have bigger groups, which still count as five of a kind.
+    pub fn from_groups(groups: &[usize]) -> Self {
+        let biggest = groups.first().copied().unwrap_or(0);
+        let second = groups.get(1).copied().unwrap_or(0);
+
+        match (biggest, second) {
+            (5.., _) => Self::FiveOfAKind,
+            (4, _) => Self::FourOfAKind,
+            (3, 2..) => Self::FullHouse,
+            (3, _) => Self::ThreeOfAKind,
+            (2, 2) => Self::TwoPair,
+            (2, _) => Self::OnePair,
+            _ => Self::HighCard,
         }
+    }
+}
+
+/// How to order hands of the same type.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TieBreak {
+    /// Compare the first cards, then the second cards, and so on, in the order they were dealt.
+    InOrder,
+    /// Compare the highest cards, then the next highest, and so on, as in poker.
+    HighestFirst,
+}
+
+/// Everything that can change between games of Camel Cards.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Rules {
+    /// Every card, from lowest to highest.
+    pub cards: Vec<char>,
+    /// Cards that count as whatever makes the best hand type. They still rank as themselves when
+    /// breaking ties.
+    pub wild: Vec<char>,
+    pub hand_size: usize,
+    pub tie_break: TieBreak,
+}
+
+impl Rules {
+    /// # Panics
+    ///
+    /// If a card is listed twice, or a wild card isn't one of the cards.
+    pub fn new(cards: &str, wild: &str, hand_size: usize, tie_break: TieBreak) -> Self {
+        let cards: Vec<char> = cards.chars().collect();
+        let wild: Vec<char> = wild.chars().collect();
+        assert!(cards.iter().collect::<HashSet<&char>>().len() == cards.len(), "cards {:?} are not all different", cards);
+        assert!(wild.iter().all(|x| cards.contains(x)), "wild cards {:?} are not all in {:?}", wild, cards);
+
+        Self { cards, wild, hand_size, tie_break }
+    }
+
+    /// The rules for part 1.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA", "", 5, TieBreak::InOrder)
+    }
+
+    /// The rules for part 2, where J is a joker that is wild but the lowest card.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA", "J", 5, TieBreak::InOrder)
+    }
 
-        return Self { cards: chars_list.clone(), hand_type: hand_type, bid: bid, joker_count: chars_list.iter().filter(|x| **x == 'J').count()};
+    /// Where a card ranks, from 0 for the lowest.
+    pub fn card_value(&self, card: char) -> Option<usize> {
+        self.cards.iter().position(|x| *x == card)
     }
 
-    fn change_joker(&self) -> Self {
-        let mut new_hand: Hand = self.clone();
-        for i in 0..new_hand.cards.len() {
-            if new_hand.cards[i] == 'J' {
-                new_hand.cards[i] = '1';
+    pub fn hand_type(&self, hand: &Hand) -> HandType {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        let mut wild_count: usize = 0;
+        for card in hand.cards.iter() {
+            match self.wild.contains(card) {
+                true => wild_count += 1,
+                false => *counts.entry(*card).or_default() += 1,
             }
         }
 
-        if new_hand.joker_count == 1 {
-            match new_hand.hand_type {
-                HandType::FiveOfAKind => (),
-                HandType::FourOfAKind => new_hand.hand_type = HandType::FiveOfAKind,
-                HandType::FullHouse => (),
-                HandType::ThreeOfAKind => new_hand.hand_type = HandType::FourOfAKind,
-                HandType::TwoPair => new_hand.hand_type = HandType::FullHouse,
-                HandType::OnePair => new_hand.hand_type = HandType::ThreeOfAKind,
-                HandType::HighCard => new_hand.hand_type = HandType::OnePair,
-            }
-        } else if new_hand.joker_count == 2 {
-            match new_hand.hand_type {
-                HandType::FiveOfAKind => (),
-                HandType::FourOfAKind => (),
-                HandType::FullHouse => new_hand.hand_type = HandType::FiveOfAKind,
-                HandType::ThreeOfAKind => (),
-                HandType::TwoPair => new_hand.hand_type = HandType::FourOfAKind,
-                HandType::OnePair => new_hand.hand_type = HandType::ThreeOfAKind,
-                HandType::HighCard => (),
-            }
-        } else if new_hand.joker_count == 3 {
-            match new_hand.hand_type {
-                HandType::FiveOfAKind => (),
-                HandType::FourOfAKind => (),
-                HandType::FullHouse => new_hand.hand_type = HandType::FiveOfAKind,
-                HandType::ThreeOfAKind => new_hand.hand_type = HandType::FourOfAKind,
-                HandType::TwoPair => (),
-                HandType::OnePair => (),
-                HandType::HighCard => (),
-            }
-        } else if new_hand.joker_count == 4 {
-            match new_hand.hand_type {
-                HandType::FiveOfAKind => (),
-                HandType::FourOfAKind => new_hand.hand_type = HandType::FiveOfAKind,
-                HandType::FullHouse => (),
-                HandType::ThreeOfAKind => (),
-                HandType::TwoPair => (),
-                HandType::OnePair => (),
-                HandType::HighCard => (),
-            }
+        let mut groups: Vec<usize> = counts.into_values().collect();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Wild cards always do best joining the biggest group.
+        match groups.first_mut() {
+            Some(biggest) => *biggest += wild_count,
+            None => groups.push(wild_count),
         }
 
-        return new_hand;
+        HandType::from_groups(&groups)
+    }
+
+    /// The type the hand would have if no cards were wild.
+    pub fn natural_hand_type(&self, hand: &Hand) -> HandType {
+        Self { wild: Vec::new(), ..self.clone() }.hand_type(hand)
     }
-}
 
-fn get_card_value(input_char: char) -> usize {
-    if input_char.is_numeric() {
-        return input_char.to_digit(10).expect("Bad char in get_card_value") as usize;
-    } else {
-        match input_char {
-            'T' => return 10,
-            'J' => return 11,
-            'Q' => return 12,
-            'K' => return 13,
-            'A' => return 14,
-            _ => return 0,
+    /// The value of each card, or an error if any aren't cards in these rules or there are the
+    /// wrong number of them.
+    pub fn card_values(&self, cards: &[char]) -> Result<Vec<usize>, AocError> {
+        let values = cards.iter().enumerate()
+            .map(|(i, x)| self.card_value(*x).ok_or_else(|| AocError::parse(i + 1, format!("'{}' is not a card", x))))
+            .collect::<Result<Vec<usize>, AocError>>()?;
+        if values.len() != self.hand_size {
+            return Err(AocError::parse(1, format!("a hand has {} cards, not {}", self.hand_size, values.len())));
         }
+
+        Ok(values)
     }
-}
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.hand_type != other.hand_type {
-            return self.hand_type.cmp(&other.hand_type);
-        } else {
-            for i in 0..self.cards.len() {
-                if self.cards[i] != other.cards[i] {
-                    return get_card_value(self.cards[i]).cmp(&get_card_value(other.cards[i]));
-                }
-            }
-            return Ordering::Equal;
+    /// Hands sort in the same order as this.
+    ///
+    /// The hand may have been read with other rules, so its cards are checked against these.
+    pub fn strength(&self, hand: &Hand) -> Result<(HandType, Vec<usize>), AocError> {
+        let mut values: Vec<usize> = self.card_values(&hand.cards)?;
+        if self.tie_break == TieBreak::HighestFirst {
+            values.sort_unstable_by(|a, b| b.cmp(a));
         }
+
+        Ok((self.hand_type(hand), values))
     }
 
-    
+    pub fn compare(&self, a: &Hand, b: &Hand) -> Result<Ordering, AocError> {
+        Ok(self.strength(a)?.cmp(&self.strength(b)?))
+    }
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.hand_type != other.hand_type {
-            return self.hand_type.partial_cmp(&other.hand_type);
-        } else {
-            for i in 0..self.cards.len() {
-                if self.cards[i] != other.cards[i] {
-                    return get_card_value(self.cards[i]).partial_cmp(&get_card_value(other.cards[i]));
-                }
-            }
-            return Some(Ordering::Equal);
-        }
+#[derive(Debug,Eq,PartialEq, Clone)]
+pub struct Hand {
+    pub cards: Vec<char>,
+    pub bid: usize,
+}
+
+impl Hand {
+    /// Parse `<cards> <bid>`, e.g. `32T3K 765`.
+    pub fn from_string(input_string: &str, rules: &Rules) -> Result<Self, AocError> {
+        let (cards_string, bid_string) = input_string.split_once(' ')
+            .ok_or(AocError::parse(input_string.len() + 1, "expected a bid after the cards"))?;
+        let cards: Vec<char> = cards_string.chars().collect();
+        rules.card_values(&cards)?;
+
+        let bid: usize = bid_string.parse::<usize>()
+            .map_err(|_| AocError::parse(cards_string.len() + 2, format!("bid '{}' is not a number", bid_string)))?;
+
+        Ok(Self { cards, bid })
     }
 }
 
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines = generic::read_in_file(input_filename.as_str());
-    let mut hands: Vec<Hand> = input_lines.iter().map(|x| Hand::from_string(x.to_string())).collect();
+/// Each hand's rank, from 1 for the weakest, in the order they were dealt.
+///
+/// An error from a hand that doesn't suit the rules is on the line of that hand.
+pub fn get_ranks(hands: &[Hand], rules: &Rules) -> Result<Vec<usize>, AocError> {
+    let strengths = hands.iter().enumerate()
+        .map(|(i, x)| rules.strength(x).map_err(|e| e.at_line(i + 1)))
+        .collect::<Result<Vec<(HandType, Vec<usize>)>, AocError>>()?;
+    let mut by_strength: Vec<usize> = (0..hands.len()).collect();
+    by_strength.sort_by(|a, b| strengths[*a].cmp(&strengths[*b]));
+
+    let mut ranks: Vec<usize> = vec![0; hands.len()];
+    for (i, hand_index) in by_strength.into_iter().enumerate() {
+        trace!("Rank {}: {:?} {:?}", i + 1, hands[hand_index], strengths[hand_index].0);
+        ranks[hand_index] = i + 1;
+    }
+
+    Ok(ranks)
+}
+
+pub fn get_total_winnings(hands: &[Hand], rules: &Rules) -> Result<usize, AocError> {
+    Ok(hands.iter().zip(get_ranks(hands, rules)?).map(|(hand, rank)| hand.bid * rank).sum())
+}
+
+/// How one hand did.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HandReport {
+    pub cards: String,
+    pub bid: usize,
+    /// The type before any wild cards are used.
+    pub natural_type: HandType,
+    pub hand_type: HandType,
+    /// From 1 for the weakest hand.
+    pub rank: usize,
+    pub winnings: usize,
+}
 
-    if part_2 {
-        // Account for Joker
-        for i in 0..hands.len() {
-            hands[i] = hands[i].change_joker();
+/// Every hand's type, rank and winnings, to see why the total came out as it did.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Report {
+    /// In the order the hands were dealt.
+    pub hands: Vec<HandReport>,
+    pub total_winnings: usize,
+}
+
+impl Report {
+    pub fn new(hands: &[Hand], rules: &Rules) -> Result<Self, AocError> {
+        let hands: Vec<HandReport> = hands.iter().zip(get_ranks(hands, rules)?).map(|(hand, rank)| HandReport {
+            cards: hand.cards.iter().collect(),
+            bid: hand.bid,
+            natural_type: rules.natural_hand_type(hand),
+            hand_type: rules.hand_type(hand),
+            rank,
+            winnings: hand.bid * rank,
+        }).collect();
+        let total_winnings = hands.iter().map(|x| x.winnings).sum();
+
+        Ok(Self { hands, total_winnings })
+    }
+
+    /// How many hands are of each type, before and after using wild cards.
+    pub fn histogram(&self) -> Vec<(HandType, usize, usize)> {
+        HandType::ALL.iter().map(|hand_type| (
+            *hand_type,
+            self.hands.iter().filter(|x| x.natural_type == *hand_type).count(),
+            self.hands.iter().filter(|x| x.hand_type == *hand_type).count(),
+        )).collect()
+    }
+
+    /// A table of the hands, without the histogram so every row has the same columns.
+    pub fn to_csv(&self) -> String {
+        let mut lines: Vec<String> = vec!["cards,bid,natural_type,hand_type,rank,winnings".to_string()];
+        for hand in self.hands.iter() {
+            lines.push(format!("{},{},{},{},{},{}", hand.cards, hand.bid, hand.natural_type.name(), hand.hand_type.name(), hand.rank, hand.winnings));
         }
+
+        lines.join("\n")
     }
 
-    hands.sort();
+    /// The histogram as a table of its own.
+    pub fn histogram_to_csv(&self) -> String {
+        let mut lines: Vec<String> = vec!["hand_type,natural_count,count".to_string()];
+        for (hand_type, natural_count, count) in self.histogram() {
+            lines.push(format!("{},{},{}", hand_type.name(), natural_count, count));
+        }
 
-    for hand in hands.iter() {
-        println!("{:?}", hand);
+        lines.join("\n")
     }
 
-    let mut total_winnings: usize = 0;
-    for i in 0..hands.len() {
-        //println!("{:?}", hands[i]);
-        total_winnings += hands[i].bid * (i + 1);
+    pub fn to_json(&self) -> String {
+        let hands: Vec<String> = self.hands.iter().map(|hand| format!(
+            "    {{\"cards\": \"{}\", \"bid\": {}, \"natural_type\": \"{}\", \"hand_type\": \"{}\", \"rank\": {}, \"winnings\": {}}}",
+            hand.cards, hand.bid, hand.natural_type.name(), hand.hand_type.name(), hand.rank, hand.winnings,
+        )).collect();
+        let histogram: Vec<String> = self.histogram().iter().map(|(hand_type, natural_count, count)| format!(
+            "    \"{}\": {{\"natural_count\": {}, \"count\": {}}}",
+            hand_type.name(), natural_count, count,
+        )).collect();
+
+        format!(
+            "{{\n  \"hands\": [\n{}\n  ],\n  \"histogram\": {{\n{}\n  }},\n  \"total_winnings\": {}\n}}",
+            hands.join(",\n"), histogram.join(",\n"), self.total_winnings,
+        )
     }
-    
+}
+
+pub struct Day7;
 
+impl Solution for Day7 {
+    type Parsed = Vec<Hand>;
+    type Answer = usize;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        // The jokers only change how hands are scored, so every hand can be read as standard.
+        let rules = Rules::standard();
+        input_lines.iter().enumerate().map(|(i, x)| Hand::from_string(x, &rules).map_err(|e| e.at_line(i + 1))).collect()
+    }
 
-    return total_winnings;
+    fn part_1(hands: &Self::Parsed) -> Self::Answer {
+        get_total_winnings(hands, &Rules::standard()).expect("hands were read with these rules")
+    }
+
+    fn part_2(hands: &Self::Parsed) -> Self::Answer {
+        // The same cards in a different order, so every hand read as standard still suits.
+        get_total_winnings(hands, &Rules::jokers()).expect("hands were read with the same cards")
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn check_hands() {
-        assert_eq!(Hand::from_string("AAAAA 0".to_string()).hand_type, HandType::FiveOfAKind);
-        assert_eq!(Hand::from_string("AA8AA 0".to_string()).hand_type, HandType::FourOfAKind);
-        assert_eq!(Hand::from_string("23332 0".to_string()).hand_type, HandType::FullHouse);
-        assert_eq!(Hand::from_string("TTT98 0".to_string()).hand_type, HandType::ThreeOfAKind);
-        assert_eq!(Hand::from_string("23432 0".to_string()).hand_type, HandType::TwoPair);
-        assert_eq!(Hand::from_string("A23A4 0".to_string()).hand_type, HandType::OnePair);
-        assert_eq!(Hand::from_string("23456 0".to_string()).hand_type, HandType::HighCard);
-
-        assert!(Hand::from_string("AAAAA 0".to_string()) > Hand::from_string("AA8AA 0".to_string()));
-
-        assert!(get_card_value('1') == 1);
-        assert!(get_card_value('9') == 9);
-        assert!(get_card_value('A') == 14);
-        assert!(get_card_value('K') == 13);
-        assert!(get_card_value('Q') == 12);
-        assert!(get_card_value('J') == 11);
-        assert!(get_card_value('T') == 10);
-
-        assert!(Hand::from_string("KK677 28".to_string()) > Hand::from_string("KTJJT 220".to_string()));
+        let rules = Rules::standard();
+        let hand = |x: &str| Hand::from_string(x, &rules).unwrap();
+        assert_eq!(rules.hand_type(&hand("AAAAA 0")), HandType::FiveOfAKind);
+        assert_eq!(rules.hand_type(&hand("AA8AA 0")), HandType::FourOfAKind);
+        assert_eq!(rules.hand_type(&hand("23332 0")), HandType::FullHouse);
+        assert_eq!(rules.hand_type(&hand("TTT98 0")), HandType::ThreeOfAKind);
+        assert_eq!(rules.hand_type(&hand("23432 0")), HandType::TwoPair);
+        assert_eq!(rules.hand_type(&hand("A23A4 0")), HandType::OnePair);
+        assert_eq!(rules.hand_type(&hand("23456 0")), HandType::HighCard);
+
+        assert_eq!(rules.compare(&hand("AAAAA 0"), &hand("AA8AA 0")), Ok(Ordering::Greater));
+
+        assert_eq!(rules.card_value('1'), None);
+        assert_eq!(rules.card_value('2'), Some(0));
+        assert_eq!(rules.card_value('9'), Some(7));
+        assert_eq!(rules.card_value('A'), Some(12));
+        assert_eq!(rules.card_value('K'), Some(11));
+        assert_eq!(rules.card_value('Q'), Some(10));
+        assert_eq!(rules.card_value('J'), Some(9));
+        assert_eq!(rules.card_value('T'), Some(8));
+
+        assert_eq!(rules.compare(&hand("KK677 28"), &hand("KTJJT 220")), Ok(Ordering::Greater));
+
+        // With jokers KTJJT becomes four of a kind, but JKKK2 loses to QQQQ2 as J is the lowest.
+        let jokers = Rules::jokers();
+        assert_eq!(jokers.hand_type(&hand("KTJJT 220")), HandType::FourOfAKind);
+        assert_eq!(jokers.hand_type(&hand("JJJJJ 0")), HandType::FiveOfAKind);
+        assert_eq!(jokers.hand_type(&hand("2345J 0")), HandType::OnePair);
+        assert_eq!(jokers.hand_type(&hand("22JJ3 0")), HandType::FourOfAKind);
+        assert_eq!(jokers.compare(&hand("JKKK2 0"), &hand("QQQQ2 0")), Ok(Ordering::Less));
+    }
+
+    #[test]
+    fn rule_variants() {
+        // Jokers and twos wild.
+        let rules = Rules::new("J23456789TQKA", "J2", 5, TieBreak::InOrder);
+        let hand = |x: &str| Hand::from_string(x, &rules).unwrap();
+        assert_eq!(rules.hand_type(&hand("2J345 0")), HandType::ThreeOfAKind);
+        assert_eq!(rules.hand_type(&hand("22JJA 0")), HandType::FiveOfAKind);
+
+        // Seven card hands.
+        let rules = Rules::new("23456789TJQKA", "", 7, TieBreak::InOrder);
+        let hand = |x: &str| Hand::from_string(x, &rules).unwrap();
+        assert_eq!(rules.hand_type(&hand("AAAAAA2 0")), HandType::FiveOfAKind);
+        assert_eq!(rules.hand_type(&hand("AAA2233 0")), HandType::FullHouse);
+        assert_eq!(rules.hand_type(&hand("AA22334 0")), HandType::TwoPair);
+        assert_eq!(Hand::from_string("AAAAA 0", &rules), Err(AocError::parse(1, "a hand has 7 cards, not 5")));
+        assert_eq!(get_total_winnings(&[hand("AAA2233 10"), hand("2345678 1"), hand("KKKK234 100")], &rules), Ok(1 + 10 * 2 + 100 * 3));
+
+        // 92345 beats 2345T card by card, but loses when the highest cards are compared first.
+        let in_order = Rules::standard();
+        let highest_first = Rules::new("23456789TJQKA", "", 5, TieBreak::HighestFirst);
+        let (a, b) = (Hand::from_string("92345 0", &in_order).unwrap(), Hand::from_string("2345T 0", &in_order).unwrap());
+        assert_eq!(in_order.compare(&a, &b), Ok(Ordering::Greater));
+        assert_eq!(highest_first.compare(&a, &b), Ok(Ordering::Less));
+
+        // A different set of cards altogether, which hands read as standard may not suit.
+        let rules = Rules::new("xyz", "z", 3, TieBreak::InOrder);
+        let hand = |x: &str| Hand::from_string(x, &rules).unwrap();
+        assert_eq!(rules.hand_type(&hand("xzy 0")), HandType::OnePair);
+        assert_eq!(rules.compare(&hand("yyx 0"), &hand("xxy 0")), Ok(Ordering::Greater));
+        assert_eq!(get_total_winnings(&[hand("yyx 10"), hand("xxy 1")], &rules), Ok(21));
+        let standard_hands = Day7::parse(vec!["32T3K 765".to_string()]).unwrap();
+        assert_eq!(rules.strength(&standard_hands[0]), Err(AocError::parse(1, "'3' is not a card")));
+        assert_eq!(get_total_winnings(&standard_hands, &rules).err().map(|e| e.to_string()), Some("1:1: '3' is not a card".to_string()));
+        assert_eq!(Report::new(&standard_hands, &Rules::new("2TK", "", 5, TieBreak::InOrder)).err(), Some(AocError::parse(1, "'3' is not a card").at_line(1)));
+        assert_eq!(get_total_winnings(&standard_hands, &Rules::new("23TK", "", 4, TieBreak::InOrder)), Err(AocError::parse(1, "a hand has 4 cards, not 5").at_line(1)));
+    }
+
+    #[test]
+    fn report() {
+        let rules = Rules::jokers();
+        let hands: Vec<Hand> = ["32T3K 765", "T55J5 684", "KK677 28", "KTJJT 220", "QQQJA 483"].iter()
+            .map(|x| Hand::from_string(x, &rules).unwrap())
+            .collect();
+        let report = Report::new(&hands, &rules).unwrap();
+
+        assert_eq!(report.total_winnings, 5905);
+        assert_eq!(Ok(report.total_winnings), get_total_winnings(&hands, &rules));
+        assert_eq!(report.hands[3], HandReport {
+            cards: "KTJJT".to_string(),
+            bid: 220,
+            natural_type: HandType::TwoPair,
+            hand_type: HandType::FourOfAKind,
+            rank: 5,
+            winnings: 1100,
+        });
+        assert_eq!(report.histogram()[2], (HandType::TwoPair, 2, 1));
+        assert_eq!(report.histogram()[5], (HandType::FourOfAKind, 0, 3));
+
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().nth(1), Some("32T3K,765,one_pair,one_pair,1,765"));
+        // Every row has the same columns, so it can be read as one table.
+        assert_eq!(csv.lines().count(), 6);
+        assert!(csv.lines().all(|x| x.split(',').count() == 6));
+
+        let histogram = report.histogram_to_csv();
+        assert_eq!(histogram.lines().next(), Some("hand_type,natural_count,count"));
+        assert_eq!(histogram.lines().nth(3), Some("two_pair,2,1"));
+        assert_eq!(histogram.lines().count(), 8);
+
+        let json = report.to_json();
+        assert!(json.contains("{\"cards\": \"T55J5\", \"bid\": 684, \"natural_type\": \"three_of_a_kind\", \"hand_type\": \"four_of_a_kind\", \"rank\": 3, \"winnings\": 2052}"));
+        assert!(json.contains("\"full_house\": {\"natural_count\": 0, \"count\": 0}"));
+        assert!(json.ends_with("\"total_winnings\": 5905\n}"));
+    }
+
+    #[test]
+    fn bad_hands() {
+        let rules = Rules::standard();
+        assert_eq!(Hand::from_string("32T3K", &rules), Err(AocError::parse(6, "expected a bid after the cards")));
+        assert_eq!(Hand::from_string("32X3K 765", &rules), Err(AocError::parse(3, "'X' is not a card")));
+        assert_eq!(Hand::from_string("32T3 765", &rules), Err(AocError::parse(1, "a hand has 5 cards, not 4")));
+        assert_eq!(Hand::from_string("32T3K 7x5", &rules), Err(AocError::parse(7, "bid '7x5' is not a number")));
+
+        let input_lines: Vec<String> = vec!["32T3K 765".to_string(), "T55J5".to_string()];
+        assert_eq!(Day7::parse(input_lines).err().map(|e| e.to_string()), Some("2:6: expected a bid after the cards".to_string()));
     }
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day7>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 6440);
+        answers::assert_answer(7, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day7>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 252295678);
+        answers::assert_answer(7, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day7>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 5905);
+        answers::assert_answer(7, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day7>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 250577259);
+        answers::assert_answer(7, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
@@ -1,11 +1,15 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_8";
 
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::cycle::Cycle;
+use crate::generic::math::{Congruence, Overflow};
+use crate::generic::Solution;
+use crate::debug;
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum Direction {
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
     Left,
     Right,
 }
@@ -13,65 +17,64 @@ enum Direction {
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct Map {
-    instructions: Vec<char>,
-    index: usize,
+    instructions: Vec<Direction>,
 }
 
 impl Map {
-    fn from_string(input_line: &String) -> Self {
-        return Self {
-            instructions: input_line.chars().collect(),
-            index: 0,
+    pub fn from_string(input_line: &str) -> Result<Self, AocError> {
+        if input_line.is_empty() {
+            return Err(AocError::parse(1, "expected a line of L and R instructions"));
         }
-    }
 
-    fn get_direction(&mut self) -> Direction {
-        let next_instruction: Direction;
-        match self.instructions[self.index] {
-            'L' => next_instruction = Direction::Left,
-            'R' => next_instruction = Direction::Right,
-            _ => next_instruction = Direction::Left,
-        }
+        let instructions = input_line.char_indices().map(|(i, c)| match c {
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            _ => Err(AocError::parse(i + 1, format!("'{}' is not an instruction", c))),
+        }).collect::<Result<Vec<Direction>, AocError>>()?;
 
-        self.index += 1;
-        self.index %= self.instructions.len();
+        Ok(Self { instructions })
+    }
 
-        return next_instruction;
+    /// The direction to take after `steps` steps from the start of the instructions.
+    fn direction_at(&self, steps: usize) -> Direction {
+        self.instructions[steps % self.instructions.len()]
     }
 }
 
 
+/// One line of the network, as written.
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct NetworkNode {
-    start: String,
-    left: String,
-    right: String,
+pub struct NetworkNode {
+    pub start: String,
+    pub left: String,
+    pub right: String,
 }
 
 impl NetworkNode {
-    fn from_string(input_line: &String) -> Self {
-        return Self {
-            start: input_line[..3].to_string(),
-            left: input_line[7..10].to_string(),
-            right: input_line[12..15].to_string(),
-        }
-    }
-
-    fn get_next_node(&self, direction: Direction) -> String {
-        match direction {
-            Direction::Left => self.left.clone(),
-            Direction::Right => self.right.clone(),
+    /// Parse `AAA = (BBB, CCC)`. Labels can be any length.
+    pub fn from_string(input_line: &str) -> Result<Self, AocError> {
+        let (start, links) = input_line.split_once(" = ").ok_or(AocError::parse(1, "expected `AAA = (BBB, CCC)`"))?;
+        let links_column = start.len() + 4;
+        let (left, right) = links.strip_prefix('(').and_then(|x| x.strip_suffix(')')).and_then(|x| x.split_once(", "))
+            .ok_or(AocError::parse(links_column, "expected `(BBB, CCC)` after the node"))?;
+
+        for (column, label) in [(1, start), (links_column + 1, left), (links_column + left.len() + 3, right)] {
+            if label.is_empty() || !label.chars().all(|x| x.is_ascii_alphanumeric()) {
+                return Err(AocError::parse(column, format!("'{}' is not a node label", label)));
+            }
         }
-    }
 
-    fn is_finished(&self) -> bool {
-        match self.start.chars().nth(2).expect("Node start is too small") {
-            'Z' => true,
-            _ => false,
-        }
+        Ok(Self {
+            start: start.to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+        })
     }
 }
 
+/// Nodes are numbered in the order their labels first appear.
+pub type NodeId = u32;
+
 fn _all_true(input_bool: Vec<bool>) -> bool {
     let bool_set: HashSet<bool> = HashSet::from_iter(input_bool.clone());
     match bool_set.len() {
@@ -80,111 +83,472 @@ fn _all_true(input_bool: Vec<bool>) -> bool {
     }
 }
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut map: Map = Map::from_string(&input_lines[0]);
-    let nodes: Vec<NetworkNode> = input_lines[2..].iter().map(|x| NetworkNode::from_string(x)).collect();
-    let nodes_hash: HashMap<String, NetworkNode> = nodes.iter().map(|x| (x.start.clone(), x.clone())).collect::<HashMap<String, NetworkNode>>();
-
-    let mut start_nodes: Vec<&NetworkNode> = Vec::new();
-    if part_2 {
-        for n in nodes.iter() {
-            if n.start.chars().nth(2).expect("Node start too short") == 'A' {
-                start_nodes.push(n);
+/// Colours for each ghost's part of the network when drawn, reused if there are more ghosts.
+const GHOST_COLOURS: [&str; 8] = ["red", "blue", "darkgreen", "orange", "purple", "brown", "magenta", "cyan"];
+
+/// Walks that haven't repeated a state after this many steps are simulated instead.
+const MAX_CYCLE_STATES: usize = 1_000_000_000;
+
+/// When one ghost is on a Z node, found from where its walk starts repeating.
+///
+/// A ghost's state is its node and where it is in the instructions. There are only so many, so
+/// every walk ends up going round the same loop of states forever.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GhostCycle {
+    /// Steps before the loop starts that end on a Z node.
+    pub prefix_hits: Vec<usize>,
+    pub cycle: Cycle,
+    /// Steps in the first lap of the loop that end on a Z node. Adding any number of laps to
+    /// these gives every later one.
+    pub cycle_hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    pub fn is_hit(&self, steps: usize) -> bool {
+        match steps < self.cycle.start {
+            true => self.prefix_hits.binary_search(&steps).is_ok(),
+            false => self.cycle_hits.binary_search(&self.cycle.first_equivalent(steps)).is_ok(),
+        }
+    }
+}
+
+/// The fewest steps after which every ghost is on a Z node at once, if that ever happens.
+///
+/// This doesn't assume each ghost has one Z node a whole loop from its start, which is what
+/// taking the lcm of the first hits relies on. It is an error if the ghosts' loops are too long to
+/// combine in a `u128`, rather than saying they never meet.
+pub fn earliest_arrival(ghosts: &[GhostCycle]) -> Result<Option<u128>, Overflow> {
+    let all_cycling = ghosts.iter().map(|x| x.cycle.start).max().unwrap_or(0);
+
+    // Until every ghost is on its loop, just check each step.
+    if let Some(steps) = (0..all_cycling).find(|x| ghosts.iter().all(|ghost| ghost.is_hit(*x))) {
+        return Ok(Some(steps as u128));
+    }
+
+    // After that each ghost is on a Z node at a number of steps that is one of its cycle hits
+    // plus some laps, so solve for every way of picking one hit from each ghost.
+    let mut congruences: Vec<Congruence> = vec![Congruence::new(0, 1)];
+    for ghost in ghosts {
+        let mut combined: Vec<Congruence> = Vec::new();
+        for congruence in congruences.iter() {
+            for hit in ghost.cycle_hits.iter() {
+                combined.extend(congruence.combine(&Congruence::new(*hit as u128, ghost.cycle.length as u128))?);
             }
         }
-    } else {
-        start_nodes = vec![nodes_hash.get("AAA").expect("AAA does not exist in hashset")];
+        congruences = combined;
+        congruences.sort_by_key(|x| x.residue);
+        congruences.dedup();
     }
+    debug!("Ghosts all meet on {:?}", congruences);
+
+    // The first time each one happens with every ghost already on its loop.
+    let all_cycling = all_cycling as u128;
+    let firsts = congruences.iter()
+        .map(|x| match x.residue >= all_cycling {
+            true => Some(x.residue),
+            false => (all_cycling - x.residue).div_ceil(x.modulus).checked_mul(x.modulus).and_then(|y| y.checked_add(x.residue)),
+        })
+        .collect::<Option<Vec<u128>>>()
+        .ok_or(Overflow)?;
+
+    Ok(firsts.into_iter().min())
+}
+
+pub struct Network {
+    map: Map,
+    labels: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    /// The left and right node from each node.
+    links: Vec<[NodeId; 2]>,
+    is_end: Vec<bool>,
+}
 
-    let mut all_steps: Vec<usize> = Vec::new();
-    println!("Start Nodes = {:?}", start_nodes);
+impl Network {
+    pub fn from_lines(input_lines: &[String]) -> Result<Self, AocError> {
+        let map = Map::from_string(input_lines.first().unwrap_or(&String::new())).map_err(|e| e.at_line(1))?;
+        let nodes: Vec<NetworkNode> = input_lines.iter().enumerate().skip(2)
+            .map(|(i, x)| NetworkNode::from_string(x).map_err(|e| e.at_line(i + 1)))
+            .collect::<Result<Vec<NetworkNode>, AocError>>()?;
+
+        let mut network = Self { map, labels: Vec::new(), ids: HashMap::new(), links: Vec::new(), is_end: Vec::new() };
+        // The line each node is defined on, and the first line each is used on.
+        let mut defined_on: Vec<Option<usize>> = Vec::new();
+        let mut used_on: Vec<usize> = Vec::new();
+
+        for (i, node) in nodes.iter().enumerate() {
+            let line_number = i + 3;
+            let [start, left, right] = [&node.start, &node.left, &node.right].map(|x| network.intern(x));
+            defined_on.resize(network.labels.len(), None);
+            used_on.resize(network.labels.len(), line_number);
+
+            if let Some(previous_line) = defined_on[start as usize] {
+                return Err(AocError::parse(1, format!("'{}' is already defined on line {}", node.start, previous_line)).at_line(line_number));
+            }
+            defined_on[start as usize] = Some(line_number);
+            network.links[start as usize] = [left, right];
+        }
 
-    for i in 0..start_nodes.len() {
-        let mut current_node = start_nodes[i];
+        if let Some(id) = defined_on.iter().position(|x| x.is_none()) {
+            return Err(AocError::parse(1, format!("'{}' is never defined", network.labels[id])).at_line(used_on[id]));
+        }
+
+        Ok(network)
+    }
+
+    fn intern(&mut self, label: &str) -> NodeId {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
+        }
+
+        let id = self.labels.len() as NodeId;
+        self.labels.push(label.to_string());
+        self.ids.insert(label.to_string(), id);
+        self.links.push([id, id]);
+        self.is_end.push(label.ends_with('Z'));
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn id(&self, label: &str) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: NodeId) -> &str {
+        &self.labels[id as usize]
+    }
+
+    pub fn next(&self, id: NodeId, direction: Direction) -> NodeId {
+        self.links[id as usize][direction as usize]
+    }
+
+    /// Ghosts start on the nodes whose labels end in A.
+    pub fn start_nodes(&self) -> Vec<NodeId> {
+        (0..self.len() as NodeId).filter(|x| self.label(*x).ends_with('A')).collect()
+    }
+
+    /// Whether a node's label ends in Z.
+    pub fn is_end(&self, id: NodeId) -> bool {
+        self.is_end[id as usize]
+    }
+
+    pub fn get_steps_to_finish(&self, start_node: NodeId) -> usize {
+        let mut current_node = start_node;
         let mut steps: usize = 0;
 
-        while !current_node.is_finished() {
-            current_node = &nodes_hash[&current_node.get_next_node(map.get_direction())];
+        while !self.is_end(current_node) {
+            current_node = self.next(current_node, self.map.direction_at(steps));
             steps += 1;
         }
 
-        all_steps.push(steps);
+        steps
     }
 
-    let mut lcm = 1;
-    for step in all_steps {
-        lcm = get_lowest_common_multiple(lcm, step);
+    /// Walk from `start_node` until a state repeats, or give up after about `max_states` steps.
+    ///
+    /// The loop is found with Brent's algorithm, which only keeps two states at a time, so long
+    /// loops need no more memory than their Z hits.
+    pub fn analyse_ghost(&self, start_node: NodeId, max_states: usize) -> Option<GhostCycle> {
+        let instruction_count = self.map.instructions.len();
+        let step = |(node, index): (NodeId, usize)| (self.next(node, self.map.instructions[index]), (index + 1) % instruction_count);
+        let start = (start_node, 0);
+
+        // Find the loop length by moving the hare on until it meets the tortoise, bringing the
+        // tortoise up to it twice as far along each time they don't.
+        let (mut power, mut length) = (1, 1);
+        let (mut tortoise, mut hare) = (start, step(start));
+        let mut steps: usize = 1;
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare;
+                power *= 2;
+                length = 0;
+            }
+            hare = step(hare);
+            length += 1;
+            steps += 1;
+            if steps > max_states {
+                return None;
+            }
+        }
+
+        // Two walkers a loop apart first meet where the loop starts.
+        let mut tortoise = start;
+        let mut hare = (0..length).fold(start, |x, _| step(x));
+        let mut cycle_start: usize = 0;
+        while tortoise != hare {
+            tortoise = step(tortoise);
+            hare = step(hare);
+            cycle_start += 1;
+        }
+        let cycle = Cycle { start: cycle_start, length };
+
+        let mut hits: Vec<usize> = Vec::new();
+        let mut state = start;
+        for steps in 0..(cycle.start + cycle.length) {
+            if self.is_end(state.0) {
+                hits.push(steps);
+            }
+            state = step(state);
+        }
+
+        let (prefix_hits, cycle_hits): (Vec<usize>, Vec<usize>) = hits.into_iter().partition(|x| *x < cycle.start);
+        Some(GhostCycle { prefix_hits, cycle, cycle_hits })
+    }
+
+    /// The nodes each ghost can get to, as a list of ghosts for each node.
+    fn reachable_by_ghosts(&self) -> Vec<Vec<usize>> {
+        let mut reachable_by: Vec<Vec<usize>> = vec![Vec::new(); self.len()];
+        for (ghost, start_node) in self.start_nodes().into_iter().enumerate() {
+            let mut to_visit: Vec<NodeId> = vec![start_node];
+            while let Some(node) = to_visit.pop() {
+                if reachable_by[node as usize].last() == Some(&ghost) {
+                    continue;
+                }
+                reachable_by[node as usize].push(ghost);
+                to_visit.extend(self.links[node as usize]);
+            }
+        }
+
+        reachable_by
+    }
+
+    /// The network in Graphviz DOT format, with start nodes filled green and end nodes red.
+    ///
+    /// With `colour_ghosts`, the nodes and links each ghost can reach are drawn in its own colour,
+    /// and any that more than one ghost can reach are drawn thick and black. The lcm of the first
+    /// Z hits is only safe to trust if no ghost shares its part of the network.
+    pub fn to_dot(&self, colour_ghosts: bool) -> String {
+        let reachable_by = self.reachable_by_ghosts();
+        let colour = |node: NodeId| match reachable_by[node as usize].as_slice() {
+            _ if !colour_ghosts => None,
+            [] => None,
+            [ghost] => Some(format!("color={}", GHOST_COLOURS[ghost % GHOST_COLOURS.len()])),
+            _ => Some("color=black, penwidth=3".to_string()),
+        };
+
+        let mut lines: Vec<String> = vec!["digraph network {".to_string()];
+        for node in 0..self.len() as NodeId {
+            let mut attributes: Vec<String> = Vec::new();
+            if self.label(node).ends_with('A') {
+                attributes.push("style=filled, fillcolor=palegreen".to_string());
+            } else if self.is_end(node) {
+                attributes.push("style=filled, fillcolor=lightcoral".to_string());
+            }
+            attributes.extend(colour(node));
+
+            match attributes.is_empty() {
+                true => lines.push(format!("    \"{}\";", self.label(node))),
+                false => lines.push(format!("    \"{}\" [{}];", self.label(node), attributes.join(", "))),
+            }
+        }
+
+        for node in 0..self.len() as NodeId {
+            let [left, right] = self.links[node as usize];
+            // Both ways to the same node is drawn as one link.
+            let edges = match left == right {
+                true => vec![(left, "LR")],
+                false => vec![(left, "L"), (right, "R")],
+            };
+            for (next, label) in edges {
+                let mut attributes: Vec<String> = vec![format!("label=\"{}\"", label)];
+                attributes.extend(colour(node));
+                lines.push(format!("    \"{}\" -> \"{}\" [{}];", self.label(node), self.label(next), attributes.join(", ")));
+            }
+        }
+        lines.push("}".to_string());
+
+        lines.join("\n")
     }
 
-    return lcm;
+    /// Move every ghost together until they are all on Z nodes. This never returns if they
+    /// never are.
+    pub fn simulate_ghosts(&self, start_nodes: &[NodeId]) -> usize {
+        let mut current_nodes: Vec<NodeId> = start_nodes.to_vec();
+        let mut steps: usize = 0;
+
+        while !current_nodes.iter().all(|x| self.is_end(*x)) {
+            let direction = self.map.direction_at(steps);
+            for node in current_nodes.iter_mut() {
+                *node = self.next(*node, direction);
+            }
+            steps += 1;
+        }
+
+        steps
+    }
 }
 
-fn get_lowest_common_multiple(a: usize, b: usize) -> usize {
-    let mut x: usize;
-    let mut y: usize;
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Parsed = Network;
+    type Answer = u128;
 
-    if a > b {
-        x = a;
-        y = b;
-    } else {
-        x = b;
-        y = a;
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        Network::from_lines(&input_lines)
     }
-    
-    let mut remainder = x % y;
 
-    while remainder != 0 {
-        x = y;
-        y = remainder;
-        remainder = x % y;
+    fn part_1(network: &Self::Parsed) -> Self::Answer {
+        network.get_steps_to_finish(network.id("AAA").expect("AAA does not exist in the network")) as u128
     }
 
-    return (a * b)/y;
+    fn part_2(network: &Self::Parsed) -> Self::Answer {
+        let start_nodes: Vec<NodeId> = network.start_nodes();
+        debug!("Start Nodes = {:?}", start_nodes.iter().map(|x| network.label(*x)).collect::<Vec<&str>>());
+
+        let ghosts: Option<Vec<GhostCycle>> = start_nodes.iter().map(|x| network.analyse_ghost(*x, MAX_CYCLE_STATES)).collect();
+        match ghosts {
+            Some(ghosts) => {
+                debug!("Ghosts = {:?}", ghosts);
+                earliest_arrival(&ghosts)
+                    .expect("the ghosts' loops are too long to find when they meet in a u128")
+                    .expect("the ghosts are never all on Z nodes at once")
+            },
+            None => {
+                debug!("No cycle found within {} steps, simulating instead", MAX_CYCLE_STATES);
+                network.simulate_ghosts(&start_nodes) as u128
+            },
+        }
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
         // Do a quick test here
-        println!("{}", get_lowest_common_multiple(5, 3));
+    }
+
+    #[test]
+    fn bad_nodes() {
+        let node = NetworkNode::from_string;
+        assert_eq!(node("AAA = (BBB, CCC)").map(|x| x.right), Ok("CCC".to_string()));
+        assert_eq!(node("AAA (BBB, CCC)"), Err(AocError::parse(1, "expected `AAA = (BBB, CCC)`")));
+        assert_eq!(node("AAA = BBB, CCC"), Err(AocError::parse(7, "expected `(BBB, CCC)` after the node")));
+        assert_eq!(node("AAA = (BBB, CC)").map(|x| x.right), Ok("CC".to_string()));
+        assert_eq!(node("AAA = (BBB, )"), Err(AocError::parse(13, "'' is not a node label")));
+
+        let error = |x: &[&str]| Network::from_lines(&x.iter().map(|x| x.to_string()).collect::<Vec<String>>()).err().map(|e| e.to_string());
+        assert_eq!(error(&["LR", "", "AAA = (BBB, BBB)", "BBB = (AAA, Z-Z)"]), Some("4:13: 'Z-Z' is not a node label".to_string()));
+        assert_eq!(error(&["LR", "", "AAA = (BBB, CCC)", "BBB = (AAA, AAA)"]), Some("3:1: 'CCC' is never defined".to_string()));
+        assert_eq!(error(&["LR", "", "AAA = (AAA, AAA)", "AAA = (AAA, AAA)"]), Some("4:1: 'AAA' is already defined on line 3".to_string()));
+        assert_eq!(Map::from_string("LRX"), Err(AocError::parse(3, "'X' is not an instruction")));
+    }
+
+    #[test]
+    fn long_labels() {
+        let input_lines: Vec<String> = ["RL", "", "START1A = (B, MIDDLE)", "B = (B, B)", "MIDDLE = (FINISHZ, B)", "FINISHZ = (FINISHZ, FINISHZ)"]
+            .iter().map(|x| x.to_string()).collect();
+        let network = Network::from_lines(&input_lines).unwrap();
+
+        assert_eq!(network.len(), 4);
+        assert_eq!(network.label(network.next(network.id("START1A").unwrap(), Direction::Right)), "MIDDLE");
+        assert_eq!(network.start_nodes(), vec![network.id("START1A").unwrap()]);
+        assert!(network.is_end(network.id("FINISHZ").unwrap()));
+        assert_eq!(network.get_steps_to_finish(network.id("START1A").unwrap()), 2);
+    }
+
+    #[test]
+    fn ghost_cycles() {
+        let network = |x: &[&str]| Network::from_lines(&x.iter().map(|x| x.to_string()).collect::<Vec<String>>()).unwrap();
+        // The first ghost is on 11Z every other step from 2, and the second every third step from
+        // 1. The lcm of their first hits would say 2, but the first time they meet is 4.
+        let network = network(&[
+            "L", "",
+            "11A = (11B, 11B)", "11B = (11Z, 11Z)", "11Z = (11B, 11B)",
+            "22A = (22Z, 22Z)", "22Z = (22B, 22B)", "22B = (22C, 22C)", "22C = (22Z, 22Z)",
+            "33A = (33Z, 33Z)", "33Z = (33B, 33B)", "33B = (33B, 33B)",
+        ]);
+        let ghost = |x: &str| network.analyse_ghost(network.id(x).unwrap(), 100).unwrap();
+
+        assert_eq!(ghost("11A"), GhostCycle { prefix_hits: vec![], cycle: Cycle { start: 1, length: 2 }, cycle_hits: vec![2] });
+        assert_eq!(ghost("22A"), GhostCycle { prefix_hits: vec![], cycle: Cycle { start: 1, length: 3 }, cycle_hits: vec![1] });
+        assert_eq!(ghost("33A"), GhostCycle { prefix_hits: vec![1], cycle: Cycle { start: 2, length: 1 }, cycle_hits: vec![] });
+        assert!(ghost("11A").is_hit(10) && !ghost("11A").is_hit(11));
+        assert!(ghost("33A").is_hit(1) && !ghost("33A").is_hit(4));
+
+        assert_eq!(earliest_arrival(&[ghost("11A"), ghost("22A")]), Ok(Some(4)));
+        assert_eq!(network.simulate_ghosts(&[network.id("11A").unwrap(), network.id("22A").unwrap()]), 4);
+        // Only in the prefix, which the loops never get back to.
+        assert_eq!(earliest_arrival(&[ghost("22A"), ghost("33A")]), Ok(Some(1)));
+        assert_eq!(earliest_arrival(&[ghost("11A"), ghost("33A")]), Ok(None));
+
+        // Loops whose lengths have an lcm too big for a u128 are an error, not a sign they never meet.
+        let long_loop = |length: usize| GhostCycle { prefix_hits: vec![], cycle: Cycle { start: 0, length }, cycle_hits: vec![1] };
+        let primes = [18446744073709551557, 18446744073709551533, 18446744073709551521];
+        assert_eq!(earliest_arrival(&primes[..2].iter().map(|x| long_loop(*x)).collect::<Vec<GhostCycle>>()), Ok(Some(1)));
+        assert_eq!(earliest_arrival(&primes.iter().map(|x| long_loop(*x)).collect::<Vec<GhostCycle>>()), Err(Overflow));
+        assert_eq!(network.analyse_ghost(network.id("22A").unwrap(), 3), None);
+    }
+
+    #[test]
+    fn dot_export() {
+        let input_lines: Vec<String> = ["LR", "", "11A = (11B, XXX)", "11B = (11Z, 11Z)", "11Z = (11B, 11B)", "22A = (XXX, XXX)", "XXX = (XXX, 11B)"]
+            .iter().map(|x| x.to_string()).collect();
+        let network = Network::from_lines(&input_lines).unwrap();
+
+        let dot = network.to_dot(false);
+        assert!(dot.starts_with("digraph network {\n    \"11A\" [style=filled, fillcolor=palegreen];\n    \"11B\";\n"));
+        assert!(dot.contains("\n    \"11Z\" [style=filled, fillcolor=lightcoral];\n"));
+        assert!(dot.contains("\n    \"11A\" -> \"11B\" [label=\"L\"];\n    \"11A\" -> \"XXX\" [label=\"R\"];\n"));
+        assert!(dot.contains("\n    \"11B\" -> \"11Z\" [label=\"LR\"];\n"));
+        assert!(dot.ends_with("\n}"));
+
+        // Only 11A gets to 11A, but both ghosts get everywhere XXX goes.
+        let dot = network.to_dot(true);
+        assert!(dot.contains("\n    \"11A\" [style=filled, fillcolor=palegreen, color=red];\n"));
+        assert!(dot.contains("\n    \"22A\" [style=filled, fillcolor=palegreen, color=blue];\n"));
+        assert!(dot.contains("\n    \"11B\" [color=black, penwidth=3];\n"));
+        assert!(dot.contains("\n    \"11A\" -> \"XXX\" [label=\"R\", color=red];\n"));
     }
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day8>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 2);
+        answers::assert_answer(8, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2_part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_2.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_2.txt";
+        let answer = generic::solve::<Day8>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 6);
+        answers::assert_answer(8, 1, &input_filename, answer);
     }
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day8>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 15989);
+        answers::assert_answer(8, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_1_part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_3.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_3.txt";
+        let answer = generic::solve::<Day8>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 6);
+        answers::assert_answer(8, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day8>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 13830919117339);
+        answers::assert_answer(8, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
@@ -1,28 +1,36 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_9";
 
 #[warn(dead_code)]
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::{self, Solution};
 use std::collections::HashSet;
 
 #[derive(Debug,Eq,PartialEq, Clone)]
-struct History {
-    sequence: Vec<i32>,
-    gradients: Vec<Vec<i32>>,
+pub struct History {
+    pub sequence: Vec<i32>,
+    pub gradients: Vec<Vec<i32>>,
 }
 
 impl History {
-    fn from_string(input_string: &String) -> Self {
-        return Self {
-            sequence: input_string.split(" ").map(|x| x.parse::<i32>().expect("Input is not a number")).collect::<Vec<i32>>(),
-            gradients: Vec::new(),
+    /// Parse the numbers in a history, e.g. `0 3 6 9 12 15`.
+    pub fn from_string(input_string: &str) -> Result<Self, AocError> {
+        let sequence: Vec<i32> = generic::parse_numbers(input_string, ' ')?;
+        if sequence.len() < 2 {
+            return Err(AocError::parse(1, format!("a history needs at least 2 numbers, found {}", sequence.len())));
         }
+
+        Ok(Self { sequence, gradients: Vec::new() })
     }
 
-    fn calculate_gradients(mut self) -> Self {
+    /// Take differences until they are all zero, which needs enough numbers in the history.
+    pub fn calculate_gradients(mut self) -> Result<Self, AocError> {
         let mut current_sequence = self.sequence.clone();
         let mut sequence_set: HashSet<i32> = HashSet::from_iter(current_sequence.clone());
 
         while !(sequence_set.len() == 1 && sequence_set.get(&0) != None) {
+            if current_sequence.len() < 2 {
+                return Err(AocError::parse(1, "the differences run out before they are all zero"));
+            }
             let mut gradients: Vec<i32> = Vec::new();
             for i in 0..(current_sequence.len() - 1) {
                 gradients.push(current_sequence[i+1] - current_sequence[i]);
@@ -32,24 +40,23 @@ impl History {
             self.gradients.push(gradients);
         }
 
-        return self;
+        Ok(self)
     }
 
-    fn extrapolate_next_number(&self) -> i32 {
+    pub fn extrapolate_next_number(&self) -> i32 {
         let mut next_number: i32 = 0;
-        for i in (0..self.gradients.len() - 1).rev() {
+        // A history that is all zeros already has no gradients.
+        for i in (0..self.gradients.len().saturating_sub(1)).rev() {
             next_number = self.gradients[i].last().expect("Gradient vector empty").clone() + next_number;
         }
 
         return self.sequence.last().expect("sequence is empty") + next_number;
     }
 
-    fn extrapolate_previous_number(&self) -> i32 {
+    pub fn extrapolate_previous_number(&self) -> i32 {
         let mut previous_number: i32 = 0;
-        for i in (0..self.gradients.len() - 1).rev() {
-            //println!("\tlast gradient = {}", next_number);
+        for i in (0..self.gradients.len().saturating_sub(1)).rev() {
             previous_number = self.gradients[i].first().expect("Gradient vector empty").clone() - previous_number;
-            //println!("\t\tnext number = {}", next_number);
         }
 
         return self.sequence.first().expect("sequence is empty") - previous_number;
@@ -57,59 +64,79 @@ impl History {
 }
 
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
-    let mut histories: Vec<History> = input_lines.iter().map(|x| History::from_string(x)).collect::<Vec<History>>();
+pub struct Day9;
 
-    histories = histories.iter().map(|x| x.clone().calculate_gradients()).collect::<Vec<History>>();
+impl Solution for Day9 {
+    type Parsed = Vec<History>;
+    type Answer = i64;
 
-    let mut extrapolated_numbers: Vec<i32> = Vec::new();
-    for h in histories {
-        if !part_2 {
-            extrapolated_numbers.push(h.extrapolate_next_number());
-        } else {
-            extrapolated_numbers.push(h.extrapolate_previous_number());
-        }
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        input_lines.iter().enumerate()
+            .map(|(i, x)| History::from_string(x).and_then(History::calculate_gradients).map_err(|e| e.at_line(i + 1)))
+            .collect()
+    }
+
+    fn part_1(histories: &Self::Parsed) -> Self::Answer {
+        histories.iter().map(|h| h.extrapolate_next_number() as i64).sum()
     }
 
-    return extrapolated_numbers.iter().sum::<i32>() as usize;
+    fn part_2(histories: &Self::Parsed) -> Self::Answer {
+        histories.iter().map(|h| h.extrapolate_previous_number() as i64).sum()
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
         // Do a quick test here
     }
 
+    #[test]
+    fn bad_histories() {
+        assert_eq!(History::from_string("0 3 6 9").and_then(History::calculate_gradients).map(|x| x.extrapolate_next_number()), Ok(12));
+        assert_eq!(History::from_string("0 0").and_then(History::calculate_gradients).map(|x| x.extrapolate_previous_number()), Ok(0));
+        assert_eq!(History::from_string("0 3 x 9").err(), Some(AocError::parse(5, "'x' is not a number")));
+        assert_eq!(History::from_string("7").err(), Some(AocError::parse(1, "a history needs at least 2 numbers, found 1")));
+        assert_eq!(History::from_string("1 2 4").and_then(History::calculate_gradients).err(), Some(AocError::parse(1, "the differences run out before they are all zero")));
+
+        let input_lines: Vec<String> = vec!["0 3 6 9".to_string(), "1 3 x".to_string()];
+        assert_eq!(Day9::parse(input_lines).err().map(|e| e.to_string()), Some("2:5: 'x' is not a number".to_string()));
+    }
+
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day9>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 114);
+        answers::assert_answer(9, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day9>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 1995001648);
+        answers::assert_answer(9, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<Day9>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 2);
+        answers::assert_answer(9, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<Day9>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 988);
+        answers::assert_answer(9, 2, &input_filename, answer);
     }
 }
\ No newline at end of file
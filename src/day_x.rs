@@ -1,20 +1,33 @@
 const INPUTS_FOLDER: &str = "src/inputs/day_x";
 
-use crate::generic;
+use crate::error::AocError;
+use crate::generic::Solution;
 
 
-fn solve_puzzle(input_filename: String, part_2: bool) -> usize {
-    let input_lines: Vec<String> = generic::read_in_file(input_filename.as_str());
+pub struct DayX;
 
+impl Solution for DayX {
+    type Parsed = Vec<String>;
+    type Answer = usize;
 
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError> {
+        Ok(input_lines)
+    }
+
+    fn part_1(_parsed: &Self::Parsed) -> Self::Answer {
+        0
+    }
 
-    return 0;
+    fn part_2(_parsed: &Self::Parsed) -> Self::Answer {
+        0
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers, generic};
 
     #[test]
     fn quick_test() {
@@ -23,29 +36,33 @@ mod tests {
 
     #[test]
     fn example_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<DayX>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 13);
+        answers::assert_answer(0, 1, &input_filename, answer);
     }
 
     #[test]
     fn part_1() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", false);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<DayX>(&input_filename, false);
         println!("Answer = {:?}", answer);
-        assert!(answer == 21138);
+        answers::assert_answer(0, 1, &input_filename, answer);
     }
 
     #[test]
     fn example_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input_example_1.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input_example_1.txt";
+        let answer = generic::solve::<DayX>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 30);
+        answers::assert_answer(0, 2, &input_filename, answer);
     }
 
     #[test]
     fn part_2() {
-        let answer = solve_puzzle(INPUTS_FOLDER.to_owned() + "/input.txt", true);
+        let input_filename = INPUTS_FOLDER.to_owned() + "/input.txt";
+        let answer = generic::solve::<DayX>(&input_filename, true);
         println!("Answer = {:?}", answer);
-        assert!(answer == 7185540);
+        answers::assert_answer(0, 2, &input_filename, answer);
     }
-}
\ No newline at end of file
+}
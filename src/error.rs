@@ -0,0 +1,114 @@
+use std::fmt::Display;
+
+/// Everything that can go wrong reading and parsing a puzzle input.
+///
+/// Parsers only know where they are within a line, so a `Parse` error starts with just a column
+/// and message, and the line number and file name are filled in by the callers that know them.
+/// Lines and columns count from 1, with 0 meaning not known yet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AocError {
+    Io { filename: String, message: String },
+    Parse { filename: String, line: usize, column: usize, message: String },
+}
+
+impl AocError {
+    pub fn parse(column: usize, message: impl Into<String>) -> Self {
+        Self::Parse { filename: String::new(), line: 0, column, message: message.into() }
+    }
+
+    pub fn at_line(mut self, line_number: usize) -> Self {
+        if let Self::Parse { line, .. } = &mut self {
+            *line = line_number;
+        }
+        self
+    }
+
+    /// Move the line along, for errors from parsers given a block of lines from further down.
+    pub fn offset_line(mut self, offset: usize) -> Self {
+        if let Self::Parse { line, .. } = &mut self {
+            *line += offset;
+        }
+        self
+    }
+
+    /// Move the column along, for errors from parsers given part of a line.
+    pub fn offset_column(mut self, offset: usize) -> Self {
+        if let Self::Parse { column, .. } = &mut self {
+            *column += offset;
+        }
+        self
+    }
+
+    pub fn in_file(mut self, input_filename: &str) -> Self {
+        if let Self::Parse { filename, .. } = &mut self {
+            *filename = input_filename.to_string();
+        }
+        self
+    }
+
+    /// The error followed by the line it refers to, with a marker under the offending column.
+    pub fn show_line(&self, input_lines: &[String]) -> String {
+        match self {
+            Self::Parse { line, column, .. } if *line > 0 && *line <= input_lines.len() => {
+                let marker = " ".repeat(column.saturating_sub(1)) + "^";
+                format!("{}\n    {}\n    {}", self, input_lines[line - 1], marker)
+            },
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { filename, message } => write!(f, "unable to read {}: {}", filename, message),
+            Self::Parse { filename, line, column, message } => {
+                let mut location: Vec<String> = Vec::new();
+                if !filename.is_empty() {
+                    location.push(filename.clone());
+                }
+                if *line > 0 {
+                    location.push(line.to_string());
+                }
+                if *column > 0 {
+                    location.push(column.to_string());
+                }
+
+                match location.is_empty() {
+                    true => write!(f, "{}", message),
+                    false => write!(f, "{}: {}", location.join(":"), message),
+                }
+            },
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = AocError::parse(4, "bad card 'X'");
+        assert_eq!(error.to_string(), "4: bad card 'X'");
+        assert_eq!(error.clone().at_line(2).to_string(), "2:4: bad card 'X'");
+        assert_eq!(error.at_line(2).offset_column(3).in_file("input.txt").to_string(), "input.txt:2:7: bad card 'X'");
+        assert_eq!(AocError::parse(0, "empty input").to_string(), "empty input");
+        assert_eq!(AocError::parse(0, "row is 2 long").at_line(1).offset_line(4).to_string(), "5: row is 2 long");
+
+        let io_error = AocError::Io { filename: "input.txt".to_string(), message: "not found".to_string() };
+        assert_eq!(io_error.clone().at_line(3).in_file("other.txt"), io_error);
+        assert_eq!(io_error.to_string(), "unable to read input.txt: not found");
+    }
+
+    #[test]
+    fn show_line() {
+        let input_lines: Vec<String> = vec!["32T3K 765".to_string(), "T5X5J 684".to_string()];
+        let error = AocError::parse(3, "bad card 'X'").at_line(2).in_file("input.txt");
+        assert_eq!(error.show_line(&input_lines), "input.txt:2:3: bad card 'X'\n    T5X5J 684\n      ^");
+        assert_eq!(error.clone().at_line(3).show_line(&input_lines), "input.txt:3:3: bad card 'X'");
+    }
+}
@@ -1,11 +1,67 @@
+use std::fmt::Display;
 use std::fs;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
-pub fn read_in_file(input_filename: &str) -> Vec<String> {
-    let error_msg = format!("unable to read file {}", input_filename);
-    let input_data = fs::read_to_string(input_filename).expect(error_msg.as_str());
-    let split = input_data.lines();
-    let file_lines: Vec<String> = split.map(str::to_string).collect();
-    return file_lines.clone();
+use crate::error::AocError;
+use self::direction::{Direction, Position};
+
+pub mod cycle;
+pub mod direction;
+pub mod interval;
+pub mod math;
+pub mod pathfinding;
+
+/// Common entry point for every day's puzzle.
+///
+/// The input is parsed once into `Parsed`, and both parts work from that.
+pub trait Solution {
+    type Parsed;
+    type Answer: Display + PartialEq;
+
+    fn parse(input_lines: Vec<String>) -> Result<Self::Parsed, AocError>;
+    fn part_1(parsed: &Self::Parsed) -> Self::Answer;
+    fn part_2(parsed: &Self::Parsed) -> Self::Answer;
+}
+
+/// Read, parse and solve one part of a puzzle in a single call.
+pub fn try_solve<S: Solution>(input_filename: &str, part_2: bool) -> Result<S::Answer, AocError> {
+    let parsed = S::parse(read_in_file(input_filename)?).map_err(|e| e.in_file(input_filename))?;
+    if part_2 {
+        Ok(S::part_2(&parsed))
+    } else {
+        Ok(S::part_1(&parsed))
+    }
+}
+
+/// `try_solve` for tests, panicking if the input can't be read or parsed.
+pub fn solve<S: Solution>(input_filename: &str, part_2: bool) -> S::Answer {
+    try_solve::<S>(input_filename, part_2).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn read_in_file(input_filename: &str) -> Result<Vec<String>, AocError> {
+    let input_data = fs::read_to_string(input_filename)
+        .map_err(|e| AocError::Io { filename: input_filename.to_string(), message: e.to_string() })?;
+    Ok(input_data.lines().map(str::to_string).collect())
+}
+
+/// Parse numbers split by `separator`, e.g. `0 3 6 9` or `1,1,3`.
+///
+/// Empty pieces are skipped, so numbers lined up with extra spaces still read. Errors have the
+/// column of the bad number within `input_string`.
+pub fn parse_numbers<T: FromStr>(input_string: &str, separator: char) -> Result<Vec<T>, AocError> {
+    let mut numbers: Vec<T> = Vec::new();
+    let mut column: usize = 1;
+    for number_string in input_string.split(separator) {
+        if !number_string.is_empty() {
+            let number = number_string.parse::<T>()
+                .map_err(|_| AocError::parse(column, format!("'{}' is not a number", number_string)))?;
+            numbers.push(number);
+        }
+        column += number_string.len() + separator.len_utf8();
+    }
+
+    Ok(numbers)
 }
 
 pub fn print_2d_map(input_map: &Vec<Vec<i32>>) {
@@ -15,4 +71,294 @@ pub fn print_2d_map(input_map: &Vec<Vec<i32>>) {
         }
         print!("\n");
     }
-}
\ No newline at end of file
+}
+
+/// A rectangular map of cells, indexed by `(row, col)` from the top left.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, value: T) -> Self where T: Clone {
+        Self { rows, cols, cells: vec![value; rows * cols] }
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..rows * cols).map(|i| cell(i / cols, i % cols)).collect();
+        Self { rows, cols, cells }
+    }
+
+    /// Build a grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, AocError> {
+        let cols = rows.first().map(|x| x.len()).unwrap_or(0);
+        let row_count = rows.len();
+        let mut cells: Vec<T> = Vec::with_capacity(row_count * cols);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != cols {
+                return Err(AocError::parse(0, format!("row is {} long, expected {}", row.len(), cols)).at_line(row_index + 1));
+            }
+            cells.extend(row);
+        }
+
+        Ok(Self { rows: row_count, cols, cells })
+    }
+
+    /// Parse one cell from each character of the input lines.
+    ///
+    /// `mapper` only sees the character, so its errors should have a column of 0; the position of
+    /// the character is added here.
+    pub fn parse(input_lines: &[String], mut mapper: impl FnMut(char) -> Result<T, AocError>) -> Result<Self, AocError> {
+        let rows = input_lines.iter().enumerate().map(|(row, line)| {
+            line.chars().enumerate().map(|(col, c)| mapper(c).map_err(|e| e.offset_column(col + 1))).collect::<Result<Vec<T>, AocError>>()
+                .map_err(|e| e.at_line(row + 1))
+        }).collect::<Result<Vec<Vec<T>>, AocError>>()?;
+
+        Self::from_rows(rows)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        match self.contains(position) {
+            true => Some(&self.cells[position.0 * self.cols + position.1]),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        match self.contains(position) {
+            true => Some(&mut self.cells[position.0 * self.cols + position.1]),
+            false => None,
+        }
+    }
+
+    /// The position `(d_row, d_col)` away from `position`, if it is still on the grid.
+    pub fn offset(&self, (row, col): (usize, usize), (d_row, d_col): (isize, isize)) -> Option<(usize, usize)> {
+        let position = (row.checked_add_signed(d_row)?, col.checked_add_signed(d_col)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// The position one step from `position`, if it is still on the grid.
+    pub fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        position.step_within(direction, self.rows, self.cols)
+    }
+
+    /// The cells above, right, below and left of `position` that are on the grid.
+    pub fn neighbours_4(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::CARDINALS.iter().filter_map(move |x| self.offset(position, x.offset()))
+    }
+
+    /// The cells surrounding `position`, including diagonals, that are on the grid, clockwise from
+    /// the one above.
+    pub fn neighbours_8(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL.iter().filter_map(move |x| self.offset(position, x.offset()))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The cells of a column, from top to bottom.
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(col < self.cols, "column {} is outside the {}x{} grid", col, self.rows, self.cols);
+        self.cells[col..].iter().step_by(self.cols)
+    }
+
+    pub fn column_mut(&mut self, col: usize) -> impl DoubleEndedIterator<Item = &mut T> {
+        assert!(col < self.cols, "column {} is outside the {}x{} grid", col, self.rows, self.cols);
+        self.cells[col..].iter_mut().step_by(self.cols)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.cols.max(1))
+    }
+
+    pub fn iter_columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.cols).map(|col| self.column(col))
+    }
+
+    /// Every cell, row by row.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let cols = self.cols;
+        (0..self.cells.len()).map(move |i| (i / cols, i % cols))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn enumerate(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn map<U>(&self, cell: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { rows: self.rows, cols: self.cols, cells: self.cells.iter().map(cell).collect() }
+    }
+
+    /// Swap rows and columns, so the first column becomes the first row.
+    pub fn transpose(&self) -> Self where T: Clone {
+        Self::from_fn(self.cols, self.rows, |row, col| self[(col, row)].clone())
+    }
+
+    /// Turn a quarter turn clockwise, so the first column becomes the first row, reversed.
+    pub fn rotate_clockwise(&self) -> Self where T: Clone {
+        Self::from_fn(self.cols, self.rows, |row, col| self[(self.rows - 1 - col, row)].clone())
+    }
+
+    /// Turn a quarter turn anticlockwise, so the last column becomes the first row.
+    pub fn rotate_anticlockwise(&self) -> Self where T: Clone {
+        Self::from_fn(self.cols, self.rows, |row, col| self[(col, self.cols - 1 - row)].clone())
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Self where T: Clone {
+        Self::from_fn(self.rows, self.cols, |row, col| self[(row, self.cols - 1 - col)].clone())
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Self where T: Clone {
+        Self::from_fn(self.rows, self.cols, |row, col| self[(self.rows - 1 - row, col)].clone())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is outside the {}x{} grid", row, col, self.rows, self.cols);
+        &self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is outside the {}x{} grid", row, col, self.rows, self.cols);
+        &mut self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        &self[(position.row, position.col)]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        &mut self[(position.row, position.col)]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_string = self.iter_rows()
+            .map(|x| x.iter().map(|x| x.to_string()).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        write!(f, "{}", display_string)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn char_grid(input_lines: &[&str]) -> Grid<char> {
+        Grid::parse(&input_lines.iter().map(|x| x.to_string()).collect::<Vec<String>>(), Ok).unwrap()
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_numbers::<i32>("0 -3  6", ' '), Ok(vec![0, -3, 6]));
+        assert_eq!(parse_numbers::<usize>("1,1,3", ','), Ok(vec![1, 1, 3]));
+        assert_eq!(parse_numbers::<usize>("", ','), Ok(vec![]));
+        assert_eq!(parse_numbers::<usize>("12  x4", ' '), Err(AocError::parse(5, "'x4' is not a number")));
+    }
+
+    #[test]
+    fn parse_grid() {
+        let grid = char_grid(&["abc", "def"]);
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let input_lines: Vec<String> = vec!["12".to_string(), "3x".to_string()];
+        let digit = |c: char| c.to_digit(10).ok_or(AocError::parse(0, format!("'{}' is not a digit", c)));
+        assert_eq!(Grid::parse(&input_lines, digit).err().map(|e| e.to_string()), Some("2:2: 'x' is not a digit".to_string()));
+
+        let ragged: Vec<String> = vec!["abc".to_string(), "de".to_string()];
+        assert_eq!(Grid::parse(&ragged, Ok).err().map(|e| e.to_string()), Some("2: row is 2 long, expected 3".to_string()));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = char_grid(&["abc", "def", "ghi"]);
+        assert_eq!(grid.neighbours_4((0, 0)).map(|x| grid[x]).collect::<String>(), "bd");
+        assert_eq!(grid.neighbours_4((1, 1)).map(|x| grid[x]).collect::<String>(), "bfhd");
+        assert_eq!(grid.neighbours_8((0, 2)).map(|x| grid[x]).collect::<String>(), "feb");
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        assert_eq!(grid.offset((2, 2), (0, 1)), None);
+        assert_eq!(grid.offset((2, 2), (-2, -1)), Some((0, 1)));
+        assert_eq!(grid.step(Position::new(2, 1), Direction::Down), None);
+        assert_eq!(grid.step(Position::new(2, 1), Direction::UpRight).map(|x| grid[x]), Some('f'));
+    }
+
+    #[test]
+    fn views() {
+        let mut grid = char_grid(&["abc", "def"]);
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.iter_columns().map(|x| x.rev().collect::<String>()).collect::<Vec<String>>(), vec!["da", "eb", "fc"]);
+        assert_eq!(grid.enumerate().find(|(_, x)| **x == 'f').map(|(position, _)| position), Some((1, 2)));
+
+        grid.column_mut(2).for_each(|x| *x = '#');
+        assert_eq!(grid.to_string(), "ab#\nde#");
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside the 2x3 grid")]
+    fn column_outside() {
+        char_grid(&["abc", "def"]).column(3).count();
+    }
+
+    #[test]
+    #[should_panic(expected = "column 0 is outside the 0x0 grid")]
+    fn column_of_empty_grid() {
+        char_grid(&[]).column_mut(0).count();
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = char_grid(&["abc", "def"]);
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+        assert_eq!(grid.map(|x| x.to_ascii_uppercase()).to_string(), "ABC\nDEF");
+    }
+}
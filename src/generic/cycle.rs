@@ -0,0 +1,98 @@
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Where a sequence of states starts repeating: the state after `start + length` steps is the
+/// same as the state after `start` steps.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as after `steps` steps.
+    pub fn first_equivalent(&self, steps: usize) -> usize {
+        match steps < self.start {
+            true => steps,
+            false => self.start + (steps - self.start) % self.length,
+        }
+    }
+}
+
+/// The state after stepping a number of times, and the cycle found on the way, if any.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Iterated<S> {
+    pub state: S,
+    pub cycle: Option<Cycle>,
+}
+
+/// Apply `step` to `start` `steps` times, skipping ahead once a state repeats.
+///
+/// Every state seen is kept until the cycle is found.
+pub fn iterate<S: Hash + Eq + Clone>(start: S, steps: usize, step: impl FnMut(&S) -> S) -> Iterated<S> {
+    iterate_by_key(start, steps, step, S::clone)
+}
+
+/// [`iterate`] keeping only a hash of each state, for states too big to keep them all.
+///
+/// Two different states with the same hash would be mistaken for a cycle. With 64 bit hashes
+/// that is very unlikely, but not impossible.
+pub fn iterate_hashed<S: Hash>(start: S, steps: usize, step: impl FnMut(&S) -> S) -> Iterated<S> {
+    iterate_by_key(start, steps, step, |state| {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        hasher.finish()
+    })
+}
+
+fn iterate_by_key<S, K: Hash + Eq>(start: S, steps: usize, mut step: impl FnMut(&S) -> S, mut key: impl FnMut(&S) -> K) -> Iterated<S> {
+    let mut first_seen: HashMap<K, usize> = HashMap::new();
+    let mut state = start;
+
+    for step_index in 0..steps {
+        match first_seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                // Every further lap of the cycle ends up back here, so only the part lap is left.
+                let cycle = Cycle { start: *entry.get(), length: step_index - entry.get() };
+                for _ in 0..(steps - step_index) % cycle.length {
+                    state = step(&state);
+                }
+                return Iterated { state, cycle: Some(cycle) };
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(step_index);
+            },
+        }
+        state = step(&state);
+    }
+
+    Iterated { state, cycle: None }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 6, then 3, 4, 5, 6 forever.
+    fn next(x: &u32) -> u32 {
+        if *x == 6 { 3 } else { x + 1 }
+    }
+
+    #[test]
+    fn find_cycle() {
+        let cycle = Cycle { start: 3, length: 4 };
+        for steps in [0, 2, 7, 8, 10, 21] {
+            let expected = (0..steps).fold(0, |x, _| next(&x));
+            let iterated = iterate(0, steps, next);
+            assert_eq!(iterated.state, expected, "after {} steps", steps);
+            assert_eq!(iterated.cycle, (steps > 7).then_some(cycle));
+            assert_eq!(iterate_hashed(0, steps, next), iterated);
+        }
+
+        assert_eq!(iterate(0, 1000000000, next).state, [3, 4, 5, 6][(1000000000 - 3) % 4]);
+        assert_eq!(cycle.first_equivalent(2), 2);
+        assert_eq!(cycle.first_equivalent(9), 5);
+    }
+}
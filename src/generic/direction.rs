@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+/// A cell on a grid, counted from the top left.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Position {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Position {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// The position one step away, or `None` if that would go above or left of the origin.
+    ///
+    /// There is no lower or right edge here; use [`Position::step_within`] or
+    /// [`super::Grid::step`] to stay on a grid.
+    pub fn step(self, direction: Direction) -> Option<Self> {
+        let (d_row, d_col) = direction.offset();
+        Some(Self { row: self.row.checked_add_signed(d_row)?, col: self.col.checked_add_signed(d_col)? })
+    }
+
+    /// The position one step away, if it is within `rows` by `cols`.
+    pub fn step_within(self, direction: Direction, rows: usize, cols: usize) -> Option<Self> {
+        self.step(direction).filter(|x| x.row < rows && x.col < cols)
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    fn from(position: Position) -> Self {
+        (position.row, position.col)
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// A way to move on a grid. Up is towards row 0 and left is towards column 0.
+///
+/// The variants go clockwise from `Up`, so turning is moving along the list.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Up, right, down and left, clockwise.
+    pub const CARDINALS: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+    /// Every direction, clockwise from up.
+    pub const ALL: [Self; 8] = [Self::Up, Self::UpRight, Self::Right, Self::DownRight, Self::Down, Self::DownLeft, Self::Left, Self::UpLeft];
+
+    /// The `(row, col)` change from taking one step this way.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::UpRight => (-1, 1),
+            Self::Right => (0, 1),
+            Self::DownRight => (1, 1),
+            Self::Down => (1, 0),
+            Self::DownLeft => (1, -1),
+            Self::Left => (0, -1),
+            Self::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn is_cardinal(&self) -> bool {
+        Self::CARDINALS.contains(self)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+
+    /// Turn clockwise by `eighths` of a full turn, or anticlockwise if negative.
+    pub fn rotate(&self, eighths: isize) -> Self {
+        Self::ALL[(*self as isize + eighths).rem_euclid(8) as usize]
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    /// A quarter turn anticlockwise.
+    pub fn turn_left(&self) -> Self {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::UpLeft.rotate(1), Direction::Up);
+        assert_eq!(Direction::DownRight.opposite(), Direction::UpLeft);
+        assert!(Direction::ALL.iter().all(|x| x.opposite().opposite() == *x));
+        assert!(Direction::ALL.iter().all(|x| {
+            let (d_row, d_col) = x.offset();
+            x.opposite().offset() == (-d_row, -d_col)
+        }));
+        assert_eq!(Direction::ALL.iter().filter(|x| x.is_cardinal()).count(), 4);
+    }
+
+    #[test]
+    fn stepping() {
+        let position = Position::new(0, 2);
+        assert_eq!(position.step(Direction::Up), None);
+        assert_eq!(position.step(Direction::DownLeft), Some(Position::new(1, 1)));
+        assert_eq!(position.step(Direction::Right), Some(Position::new(0, 3)));
+        assert_eq!(position.step_within(Direction::Right, 3, 3), None);
+        assert_eq!(position.step_within(Direction::Down, 3, 3), Some(Position::new(1, 2)));
+        assert_eq!(position.manhattan_distance(&Position::new(3, 0)), 5);
+    }
+}
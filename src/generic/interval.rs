@@ -0,0 +1,167 @@
+use std::ops::Range;
+
+/// A set of integers stored as half-open ranges.
+///
+/// The ranges are always sorted, non-empty and separated by at least one missing number, so two
+/// sets holding the same numbers are equal however they were built.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<i64>>) -> Self {
+        let mut ranges: Vec<Range<i64>> = ranges.into_iter().filter(|x| !x.is_empty()).collect();
+        ranges.sort_by_key(|x| x.start);
+
+        let mut merged: Vec<Range<i64>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// The ranges making up the set, in order.
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    /// How many numbers are in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|x| x.end.abs_diff(x.start)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.ranges.first().map(|x| x.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.ranges.last().map(|x| x.end - 1)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        // The last range starting at or before the value is the only one that can hold it.
+        match self.ranges.partition_point(|x| x.start <= value) {
+            0 => false,
+            index => self.ranges[index - 1].end > value,
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.ranges.iter().chain(other.ranges.iter()).cloned())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges: Vec<Range<i64>> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+
+            // Whichever range ends first can't overlap anything else.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// The numbers in this set that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges: Vec<Range<i64>> = Vec::new();
+        let mut j = 0;
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+            // Skip ranges in other that end before this one starts.
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                if other.ranges[k].start > start {
+                    ranges.push(start..other.ranges[k].start);
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Move every number in the set by `offset`.
+    pub fn shift(&self, offset: i64) -> Self {
+        Self { ranges: self.ranges.iter().map(|x| (x.start + offset)..(x.end + offset)).collect() }
+    }
+}
+
+impl From<Range<i64>> for IntervalSet {
+    fn from(range: Range<i64>) -> Self {
+        Self::from_ranges([range])
+    }
+}
+
+impl FromIterator<Range<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(ranges: I) -> Self {
+        Self::from_ranges(ranges)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalise() {
+        let set = IntervalSet::from_ranges([10..20, 0..5, 5..7, 15..25, 30..30]);
+        assert_eq!(set.ranges(), &[0..7, 10..25]);
+        assert_eq!(set.len(), 22);
+        assert_eq!((set.min(), set.max()), (Some(0), Some(24)));
+        assert!(set.contains(6) && !set.contains(7) && set.contains(10) && !set.contains(25) && !set.contains(-1));
+        assert!(IntervalSet::from(3..3).is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = IntervalSet::from_ranges([0..10, 20..30]);
+        let b = IntervalSet::from_ranges([5..25, 28..40]);
+
+        assert_eq!(a.union(&b), IntervalSet::from(0..40));
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..40]);
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(a.shift(-5).ranges(), &[-5..5, 15..25]);
+
+        // Every number is in exactly one of a - b, a & b and b - a, or in none of them.
+        for x in -1..45 {
+            let parts = [a.difference(&b).contains(x), a.intersection(&b).contains(x), b.difference(&a).contains(x)];
+            assert_eq!(parts.iter().filter(|x| **x).count(), a.union(&b).contains(x) as usize, "{}", x);
+        }
+    }
+}
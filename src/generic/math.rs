@@ -0,0 +1,331 @@
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, RangeInclusive, Rem, Sub};
+
+/// The unsigned integer types the functions here work over.
+pub trait Unsigned: Copy + Eq + Ord + Debug
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn isqrt(self) -> Self;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(impl Unsigned for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn isqrt(self) -> Self {
+                <$t>::isqrt(self)
+            }
+        })*
+    };
+}
+
+impl_unsigned!(u32, u64, u128, usize);
+
+pub fn gcd<T: Unsigned>(a: T, b: T) -> T {
+    let (mut x, mut y) = (a, b);
+    while y != T::ZERO {
+        (x, y) = (y, x % y);
+    }
+
+    x
+}
+
+/// The lowest common multiple, or `None` if it doesn't fit in `T`.
+///
+/// Dividing by the gcd first means this only fails when the answer itself is too big.
+pub fn lcm<T: Unsigned>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The lowest common multiple of every number, which is 1 for none at all.
+pub fn lcm_all<T: Unsigned>(numbers: impl IntoIterator<Item = T>) -> Option<T> {
+    numbers.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// The largest number whose square is at most `n`, worked out without floating point.
+pub fn isqrt<T: Unsigned>(n: T) -> T {
+    n.isqrt()
+}
+
+/// `(g, x, y)` where `g` is the gcd of `a` and `b`, and `a * x + b * y == g`.
+///
+/// `g` is never negative.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    match old_r < 0 {
+        true => (-old_r, -old_x, -old_y),
+        false => (old_r, old_x, old_y),
+    }
+}
+
+/// The number that `a` times gives 1 modulo `modulus`, if `a` and `modulus` are coprime.
+///
+/// This is [`extended_gcd`] keeping only the coefficient of `a`, worked out modulo `modulus` so
+/// any `u128` modulus can be used.
+pub fn mod_inverse(a: u128, modulus: u128) -> Option<u128> {
+    let (mut old_r, mut r) = (modulus, a % modulus);
+    let (mut old_x, mut x) = (0, 1 % modulus);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, sub_mod(old_x, mul_mod(quotient, x, modulus), modulus));
+    }
+
+    (old_r == 1).then_some(old_x)
+}
+
+/// `a * b % modulus` without overflowing, however big `a` and `b` are.
+pub fn mul_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    let (mut a, mut b) = (a % modulus, b % modulus);
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    // Double and add, keeping every partial result below the modulus.
+    let mut product: u128 = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+
+    product
+}
+
+/// `(a + b) % modulus` for `a` and `b` already below the modulus.
+fn add_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    match a >= modulus - b {
+        true => a - (modulus - b),
+        false => a + b,
+    }
+}
+
+/// A result too big for a `u128`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Overflow;
+
+/// `(a - b) % modulus` for `a` and `b` already below the modulus.
+fn sub_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    match a >= b {
+        true => a - b,
+        false => modulus - (b - a),
+    }
+}
+
+/// Every number that leaves `residue` when divided by `modulus`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Congruence {
+    pub residue: u128,
+    pub modulus: u128,
+}
+
+impl Congruence {
+    pub fn new(residue: u128, modulus: u128) -> Self {
+        Self { residue: residue % modulus, modulus }
+    }
+
+    /// The numbers that are in both, or `None` if there aren't any.
+    ///
+    /// The moduli don't need to be coprime. The combined modulus is their lcm, which is an error
+    /// if it doesn't fit in a `u128`, so that can't be mistaken for there being no numbers.
+    pub fn combine(&self, other: &Self) -> Result<Option<Self>, Overflow> {
+        let g = gcd(self.modulus, other.modulus);
+        let (low, high) = (self.residue.min(other.residue), self.residue.max(other.residue));
+        if (high - low) % g != 0 {
+            return Ok(None);
+        }
+
+        // Solve residue + modulus * k == other.residue for k, modulo other.modulus / g.
+        let modulus = lcm(self.modulus, other.modulus).ok_or(Overflow)?;
+        let step_modulus = other.modulus / g;
+        let difference = match other.residue >= self.residue {
+            true => (other.residue - self.residue) / g,
+            false => step_modulus - ((self.residue - other.residue) / g) % step_modulus,
+        };
+        let inverse = mod_inverse(self.modulus / g, step_modulus).expect("coprime once the gcd is divided out");
+        let k = mul_mod(difference, inverse, step_modulus);
+
+        // self.modulus * k < modulus, so this can't overflow.
+        Ok(Some(Self { residue: add_mod(self.residue, self.modulus * k, modulus), modulus }))
+    }
+}
+
+/// The numbers meeting every congruence, as a single congruence.
+///
+/// This is the Chinese remainder theorem, generalised to moduli that share factors. `None` means
+/// no number meets them all, and an error that the combined modulus is too big for a `u128`.
+pub fn chinese_remainder(congruences: impl IntoIterator<Item = Congruence>) -> Result<Option<Congruence>, Overflow> {
+    let mut combined = Congruence::new(0, 1);
+    for congruence in congruences {
+        match combined.combine(&congruence)? {
+            Some(x) => combined = x,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(combined))
+}
+
+/// The integers `x` where `a * x^2 + b * x + c < 0`, for `a > 0`.
+///
+/// These lie strictly between the two roots, and are found exactly using `(2ax + b)^2 < b^2 - 4ac`.
+/// Returns `None` if there are none.
+///
+/// The discriminant is worked out as a `u128`, so `b^2` can be bigger than an `i128` holds.
+///
+/// # Panics
+///
+/// If `a` isn't positive, or `b^2 - 4ac` overflows a `u128`.
+pub fn quadratic_below_zero(a: i128, b: i128, c: i128) -> Option<RangeInclusive<i128>> {
+    assert!(a > 0, "the quadratic must open upwards, but a = {}", a);
+    let b_squared = b.unsigned_abs().checked_mul(b.unsigned_abs()).expect("the discriminant overflowed");
+    let four_ac = a.unsigned_abs().checked_mul(c.unsigned_abs()).and_then(|x| x.checked_mul(4));
+    let discriminant = match c > 0 {
+        // Taking away more than b^2 leaves nothing, even if it's too big to work out.
+        true => b_squared.checked_sub(four_ac?)?,
+        false => four_ac.and_then(|x| b_squared.checked_add(x)).expect("the discriminant overflowed"),
+    };
+    if discriminant == 0 {
+        return None;
+    }
+
+    // The biggest |2ax + b| can be.
+    let root = isqrt(discriminant);
+    let limit = if root * root == discriminant { root - 1 } else { root } as i128;
+
+    // -limit <= 2ax + b <= limit
+    let low = div_ceil(-limit - b, 2 * a);
+    let high = (limit - b).div_euclid(2 * a);
+    (low <= high).then_some(low..=high)
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -((-a).div_euclid(b))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0u64, 7), 7);
+        assert_eq!(lcm(4u32, 6), Some(12));
+        assert_eq!(lcm(0u32, 6), Some(0));
+        assert_eq!(lcm_all([2usize, 3, 4, 5]), Some(60));
+        // a * b overflows here, but the lcm fits.
+        assert_eq!(lcm(u64::MAX - 1, 2), Some(u64::MAX - 1));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm(u64::MAX as u128, (u64::MAX - 1) as u128), Some(u64::MAX as u128 * (u64::MAX - 1) as u128));
+    }
+
+    #[test]
+    fn square_roots() {
+        assert_eq!(isqrt(0u64), 0);
+        assert_eq!(isqrt(24u64), 4);
+        assert_eq!(isqrt(25u64), 5);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        let n: u128 = (1 << 100) + 12345;
+        assert!(isqrt(n) * isqrt(n) <= n && (isqrt(n) + 1) * (isqrt(n) + 1) > n);
+    }
+
+    #[test]
+    fn remainders() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-4, 6).0, 2);
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        // Moduli too big for an i128.
+        let big: u128 = (1 << 127) + 1;
+        assert_eq!(mul_mod(mod_inverse(2, big).unwrap(), 2, big), 1);
+        assert_eq!(mul_mod(mod_inverse(u128::MAX - 1, u128::MAX).unwrap(), u128::MAX - 1, u128::MAX), 1);
+        assert_eq!(mul_mod(u128::MAX - 1, u128::MAX - 1, u128::MAX), 1);
+
+        let congruence = |x: &[(u128, u128)]| chinese_remainder(x.iter().map(|&(r, m)| Congruence::new(r, m))).unwrap();
+        assert_eq!(congruence(&[(2, 3), (3, 5), (2, 7)]), Some(Congruence::new(23, 105)));
+        // Moduli sharing factors.
+        assert_eq!(congruence(&[(3, 4), (5, 6)]), Some(Congruence::new(11, 12)));
+        assert_eq!(congruence(&[(1, 4), (2, 6)]), None);
+        assert_eq!(congruence(&[(0, 10), (0, 15)]), Some(Congruence::new(0, 30)));
+        assert_eq!(congruence(&[]), Some(Congruence::new(0, 1)));
+
+        // Too big to combine is not the same as never meeting.
+        let big: u128 = 1 << 127;
+        assert_eq!(Congruence::new(1, big).combine(&Congruence::new(0, 3)), Err(Overflow));
+        assert_eq!(Congruence::new(1, big).combine(&Congruence::new(0, 2)), Ok(None));
+        assert_eq!(Congruence::new(1, big).combine(&Congruence::new(1, 2)), Ok(Some(Congruence::new(1, big))));
+        assert_eq!(chinese_remainder([Congruence::new(1, big), Congruence::new(0, 3)]), Err(Overflow));
+
+        // Moduli above i128::MAX are fine as long as the lcm fits. 2^127 + 1 is a multiple of 3.
+        let big: u128 = (1 << 127) + 1;
+        assert_eq!(chinese_remainder([Congruence::new(1, big)]), Ok(Some(Congruence::new(1, big))));
+        assert_eq!(chinese_remainder([Congruence::new(1, 3), Congruence::new(big - 2, big)]), Ok(Some(Congruence::new(big - 2, big))));
+        assert_eq!(chinese_remainder([Congruence::new(big - 2, big), Congruence::new(1, 3)]), Ok(Some(Congruence::new(big - 2, big))));
+        assert_eq!(chinese_remainder([Congruence::new(0, 3), Congruence::new(big - 2, big)]), Ok(None));
+
+        // Check against brute force for small moduli.
+        for (m1, m2) in [(4, 6), (9, 12), (5, 7), (8, 8), (1, 5)] {
+            for (r1, r2) in (0..m1).flat_map(|r1| (0..m2).map(move |r2| (r1, r2))) {
+                let expected = (0..m1 * m2).find(|x| x % m1 == r1 && x % m2 == r2);
+                let found = congruence(&[(r1, m1), (r2, m2)]);
+                assert_eq!(found.map(|x| x.residue), expected, "x = {} mod {}, x = {} mod {}", r1, m1, r2, m2);
+            }
+        }
+    }
+
+    #[test]
+    fn quadratics() {
+        // x^2 - 7x + 9 < 0 between the roots 1.69 and 5.30.
+        assert_eq!(quadratic_below_zero(1, -7, 9), Some(2..=5));
+        // x^2 - 30x + 200 < 0 with exact roots 10 and 20, which don't count.
+        assert_eq!(quadratic_below_zero(1, -30, 200), Some(11..=19));
+        assert_eq!(quadratic_below_zero(1, 0, -4), Some(-1..=1));
+        assert_eq!(quadratic_below_zero(1, -2, 1), None);
+        assert_eq!(quadratic_below_zero(4, 0, -1), Some(0..=0));
+        assert_eq!(quadratic_below_zero(4, -3, 0), None);
+        assert_eq!(quadratic_below_zero(1, 0, 0), None);
+
+        // b^2 is too big for an i128.
+        let b: i128 = (1 << 64) - 2;
+        let x: i128 = (1 << 63) - 1;
+        assert_eq!(quadratic_below_zero(1, -b, x * x - 1), Some(x..=x));
+        assert_eq!(quadratic_below_zero(1, -b, x * x), None);
+        assert_eq!(quadratic_below_zero(1, 1, i128::MAX), None);
+
+        for (a, b, c) in [(1, -7, 9), (3, 5, -20), (2, -11, 3), (5, 1, 1)] {
+            let expected: Vec<i128> = (-20..=20).filter(|x| a * x * x + b * x + c < 0).collect();
+            let found: Vec<i128> = quadratic_below_zero(a, b, c).map_or(Vec::new(), |x| x.collect());
+            assert_eq!(found, expected, "{}x^2 + {}x + {}", a, b, c);
+        }
+    }
+}
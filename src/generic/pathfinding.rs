@@ -0,0 +1,140 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// The cheapest way found from the start to a goal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Route<S> {
+    pub cost: usize,
+    /// Every state visited, from the start to the goal inclusive.
+    pub path: Vec<S>,
+}
+
+/// Find the cheapest route from `start` to any state where `is_goal` is true.
+///
+/// `successors` gives the states reachable from a state, each with the cost of moving there.
+/// Costs can be zero but not negative. Returns `None` if no goal can be reached.
+pub fn dijkstra<S, I>(start: S, successors: impl FnMut(&S) -> I, is_goal: impl FnMut(&S) -> bool) -> Option<Route<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(start, successors, |_| 0, is_goal)
+}
+
+/// [`dijkstra`] guided by `heuristic`, an estimate of the remaining cost from a state to a goal.
+///
+/// The route is only guaranteed to be the cheapest if the heuristic is consistent: it is zero at
+/// goals and never drops by more than the cost of a move, such as the Manhattan distance on a grid
+/// where every step costs at least 1. States are never looked at again once done, so a heuristic
+/// that only never overestimates isn't enough.
+pub fn astar<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Route<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    // States are numbered as they are found, and everything else refers to them by number.
+    let mut states: Vec<S> = vec![start.clone()];
+    let mut state_indexes: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut costs: Vec<usize> = vec![0];
+    let mut parents: Vec<usize> = vec![0];
+    let mut done: Vec<bool> = vec![false];
+
+    // Ordered by the estimated total cost, then the cost so far.
+    let mut queue: BinaryHeap<Reverse<(usize, usize, usize)>> = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), 0, 0)));
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        if done[index] || cost > costs[index] {
+            continue;
+        }
+        done[index] = true;
+
+        if is_goal(&states[index]) {
+            return Some(Route { cost, path: build_path(&states, &parents, index) });
+        }
+
+        for (next_state, step_cost) in successors(&states[index]) {
+            let next_cost = cost + step_cost;
+            let next_index = match state_indexes.get(&next_state) {
+                Some(next_index) if next_cost >= costs[*next_index] => continue,
+                Some(next_index) => *next_index,
+                None => {
+                    states.push(next_state.clone());
+                    costs.push(usize::MAX);
+                    parents.push(index);
+                    done.push(false);
+                    state_indexes.insert(next_state.clone(), states.len() - 1);
+                    states.len() - 1
+                },
+            };
+
+            costs[next_index] = next_cost;
+            parents[next_index] = index;
+            queue.push(Reverse((next_cost + heuristic(&next_state), next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+fn build_path<S: Clone>(states: &[S], parents: &[usize], end_index: usize) -> Vec<S> {
+    let mut path: Vec<S> = vec![states[end_index].clone()];
+    let mut index = end_index;
+    while index != 0 {
+        index = parents[index];
+        path.push(states[index].clone());
+    }
+    path.reverse();
+
+    path
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1-> b -1-> c -1-> d, with a shortcut a -5-> d and a dead end b -1-> e.
+    fn letters(state: &char) -> Vec<(char, usize)> {
+        match state {
+            'a' => vec![('b', 1), ('d', 5)],
+            'b' => vec![('c', 1), ('e', 1)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn cheapest_route() {
+        assert_eq!(dijkstra('a', letters, |x| *x == 'd'), Some(Route { cost: 3, path: vec!['a', 'b', 'c', 'd'] }));
+        assert_eq!(dijkstra('a', letters, |x| *x == 'a'), Some(Route { cost: 0, path: vec!['a'] }));
+        assert_eq!(dijkstra('c', letters, |x| *x == 'a'), None);
+    }
+
+    #[test]
+    fn astar_on_grid() {
+        // Walk round a wall in a 5x5 grid from the top left to the bottom left.
+        let wall = |(row, col): (i32, i32)| row == 2 && col < 4;
+        let successors = |&(row, col): &(i32, i32)| {
+            [(row - 1, col), (row + 1, col), (row, col - 1), (row, col + 1)]
+                .into_iter()
+                .filter(|&(r, c)| (0..5).contains(&r) && (0..5).contains(&c) && !wall((r, c)))
+                .map(|x| (x, 1))
+                .collect::<Vec<((i32, i32), usize)>>()
+        };
+        let goal = (4, 0);
+        let heuristic = |&(row, col): &(i32, i32)| (row.abs_diff(goal.0) + col.abs_diff(goal.1)) as usize;
+
+        let route = astar((0, 0), successors, heuristic, |x| *x == goal).unwrap();
+        assert_eq!(route.cost, 12);
+        assert_eq!(route.path.len(), 13);
+        assert!(route.path.contains(&(2, 4)));
+        assert_eq!(dijkstra((0, 0), successors, |x| *x == goal).map(|x| x.cost), Some(12));
+    }
+}
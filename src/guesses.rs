@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::Path;
+
+use crate::client::SubmitOutcome;
+
+pub const GUESSES_FILENAME: &str = "guesses.txt";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Guess {
+    pub part: usize,
+    pub answer: String,
+    pub outcome: SubmitOutcome,
+}
+
+/// Every answer submitted for a day, one `<part> <answer> <outcome>` per line.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct GuessLog {
+    pub guesses: Vec<Guess>,
+}
+
+impl GuessLog {
+    /// Load a day's guesses. A missing file means nothing has been submitted.
+    pub fn load(path: &str) -> Result<Self, String> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path).map_err(|e| format!("unable to read {}: {}", path, e))?;
+        let mut guesses: Vec<Guess> = Vec::new();
+        for (line_index, line) in contents.lines().enumerate().filter(|(_, x)| !x.trim().is_empty()) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let guess = match parts[..] {
+                [part, answer, outcome] => part.parse::<usize>().ok()
+                    .zip(SubmitOutcome::from_name(outcome))
+                    .map(|(part, outcome)| Guess { part, answer: answer.to_string(), outcome }),
+                _ => None,
+            };
+            guesses.push(guess.ok_or(format!("{}: line {} is not `<part> <answer> <outcome>`", path, line_index + 1))?);
+        }
+
+        Ok(Self { guesses })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let contents: String = self.guesses.iter().map(|x| format!("{} {} {}\n", x.part, x.answer, x.outcome.name())).collect();
+        if let Some(folder) = Path::new(path).parent() {
+            fs::create_dir_all(folder).map_err(|e| format!("unable to create {}: {}", folder.display(), e))?;
+        }
+        fs::write(path, contents).map_err(|e| format!("unable to write {}: {}", path, e))
+    }
+
+    pub fn add(&mut self, part: usize, answer: &str, outcome: SubmitOutcome) {
+        self.guesses.push(Guess { part, answer: answer.to_string(), outcome });
+    }
+
+    /// The highest answer known to be too low and the lowest known to be too high.
+    pub fn get_bounds(&self, part: usize) -> (Option<i128>, Option<i128>) {
+        let numeric_guesses = |outcome: SubmitOutcome| self.guesses.iter()
+            .filter(move |x| x.part == part && x.outcome == outcome)
+            .filter_map(|x| x.answer.parse::<i128>().ok());
+
+        (numeric_guesses(SubmitOutcome::TooLow).max(), numeric_guesses(SubmitOutcome::TooHigh).min())
+    }
+
+    /// Refuse answers that have already been judged, or that the known bounds rule out.
+    pub fn check_guess(&self, part: usize, answer: &str) -> Result<(), String> {
+        let previous = self.guesses.iter().find(|x| x.part == part && x.answer == answer && x.outcome.is_verdict());
+        if let Some(previous) = previous {
+            return match previous.outcome {
+                SubmitOutcome::Correct => Err(format!("{} has already been accepted", answer)),
+                _ => Err(format!("{} has already been submitted and was {}", answer, previous.outcome.name().replace('_', " "))),
+            };
+        }
+
+        if let Ok(number) = answer.parse::<i128>() {
+            let (too_low, too_high) = self.get_bounds(part);
+            if let Some(too_low) = too_low.filter(|x| number <= *x) {
+                return Err(format!("{} is not above {}, which was too low", answer, too_low));
+            }
+            if let Some(too_high) = too_high.filter(|x| number >= *x) {
+                return Err(format!("{} is not below {}, which was too high", answer, too_high));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_log() -> GuessLog {
+        let mut log = GuessLog::default();
+        log.add(2, "9284340", SubmitOutcome::TooLow);
+        log.add(2, "200000000", SubmitOutcome::TooHigh);
+        log.add(2, "150000000", SubmitOutcome::TooHigh);
+        log.add(2, "104070863", SubmitOutcome::RateLimited);
+        log.add(1, "abc", SubmitOutcome::Wrong);
+        log
+    }
+
+    #[test]
+    fn bounds() {
+        let log = example_log();
+        assert_eq!(log.get_bounds(2), (Some(9284340), Some(150000000)));
+        assert_eq!(log.get_bounds(1), (None, None));
+    }
+
+    #[test]
+    fn blocked_guesses() {
+        let log = example_log();
+        assert!(log.check_guess(2, "9284340").is_err());
+        assert!(log.check_guess(2, "9000000").is_err());
+        assert!(log.check_guess(2, "150000000").is_err());
+        assert!(log.check_guess(2, "175000000").is_err());
+        assert!(log.check_guess(1, "abc").is_err());
+
+        // Rate limited submissions were never judged.
+        assert!(log.check_guess(2, "104070863").is_ok());
+        assert!(log.check_guess(2, "104070862").is_ok());
+        assert!(log.check_guess(1, "9000000").is_ok());
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir().join(format!("aoc_2023_guesses_{}.txt", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let log = example_log();
+
+        log.save(&path).unwrap();
+        assert_eq!(GuessLog::load(&path), Ok(log));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(GuessLog::load(&path), Ok(GuessLog::default()));
+    }
+}
@@ -0,0 +1,43 @@
+//! Advent of Code 2023 solutions.
+//!
+//! Each `day_N` module has a `DayN` type implementing [`generic::Solution`], along with the model its
+//! input is parsed into. Solve a day from lines already in memory with:
+//!
+//! ```
+//! use aoc_2023::day_6::Day6;
+//! use aoc_2023::generic::Solution;
+//!
+//! let races = Day6::parse(vec!["Time:      7  15   30".to_string(), "Distance:  9  40  200".to_string()])?;
+//! assert_eq!(Day6::part_1(&races), 288);
+//! assert_eq!(races[1].get_ways_to_win(), 8);
+//! # Ok::<(), aoc_2023::error::AocError>(())
+//! ```
+//!
+//! or from a file with [`generic::try_solve`]. Malformed input is reported as an [`error::AocError`]
+//! giving the line and column it was found at. The other modules support the `AOC_2023` command-line tool.
+#![allow(dead_code)]
+pub mod answers;
+pub mod client;
+pub mod error;
+pub mod generic;
+pub mod guesses;
+pub mod log;
+pub mod runner;
+pub mod scaffold;
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
@@ -0,0 +1,170 @@
+use std::fmt::{Arguments, Display};
+use std::sync::RwLock;
+
+/// Environment variable read for the log filter when none has been set.
+pub const LOG_ENV: &str = "AOC_LOG";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "warn" => Some(Self::Warn),
+            "info" => Some(Self::Info),
+            "debug" => Some(Self::Debug),
+            "trace" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_string = match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        };
+        f.pad(display_string)
+    }
+}
+
+/// Which messages are shown: a default level, plus overrides for particular modules.
+///
+/// Written as a comma separated list such as `info,day_5=trace,day_14=debug`. A module named
+/// without a level shows everything from it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Filter {
+    default: Level,
+    modules: Vec<(String, Level)>,
+}
+
+impl Filter {
+    const QUIET: Self = Self { default: Level::Warn, modules: Vec::new() };
+
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let mut filter = Self::QUIET;
+        for item in spec.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            match item.split_once('=') {
+                Some((module, level_name)) => {
+                    let level = Level::from_name(level_name).ok_or(format!("unknown log level '{}'", level_name))?;
+                    filter.modules.push((module.to_string(), level));
+                },
+                None => match Level::from_name(item) {
+                    Some(level) => filter.default = level,
+                    None => filter.modules.push((item.to_string(), Level::Trace)),
+                },
+            }
+        }
+
+        Ok(filter)
+    }
+
+    pub fn enabled(&self, level: Level, module_path: &str) -> bool {
+        let module = short_module(module_path);
+        let max_level = self.modules.iter().rev().find(|(name, _)| name == module).map(|(_, x)| *x).unwrap_or(self.default);
+        level <= max_level
+    }
+}
+
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+/// Replace the filter, e.g. with one given on the command line.
+pub fn set_filter(filter: Filter) {
+    *FILTER.write().unwrap() = Some(filter);
+}
+
+/// The filter in use, read from `AOC_LOG` the first time it's needed if none has been set.
+pub fn filter() -> Filter {
+    if let Some(filter) = FILTER.read().unwrap().as_ref() {
+        return filter.clone();
+    }
+
+    let filter = match std::env::var(LOG_ENV) {
+        Ok(spec) => Filter::from_spec(&spec).unwrap_or_else(|e| {
+            eprintln!("Ignoring {}: {}", LOG_ENV, e);
+            Filter::QUIET
+        }),
+        Err(_) => Filter::QUIET,
+    };
+    FILTER.write().unwrap().get_or_insert(filter).clone()
+}
+
+pub fn enabled(level: Level, module_path: &str) -> bool {
+    if let Some(filter) = FILTER.read().unwrap().as_ref() {
+        return filter.enabled(level, module_path);
+    }
+    filter().enabled(level, module_path)
+}
+
+/// Write a message to stderr if the filter allows it. Use the `error!` to `trace!` macros rather
+/// than calling this directly.
+pub fn log(level: Level, module_path: &str, args: Arguments) {
+    if enabled(level, module_path) {
+        eprintln!("[{:<5} {}] {}", level, short_module(module_path), args);
+    }
+}
+
+/// `aoc_2023::day_5` becomes `day_5`.
+fn short_module(module_path: &str) -> &str {
+    module_path.split("::").nth(1).unwrap_or(module_path)
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Error, module_path!(), format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Warn, module_path!(), format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Info, module_path!(), format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Debug, module_path!(), format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Trace, module_path!(), format_args!($($arg)+)) };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_filter() {
+        let filter = Filter::from_spec("info,day_5=trace,day_14").unwrap();
+        assert!(filter.enabled(Level::Info, "aoc_2023::day_1"));
+        assert!(!filter.enabled(Level::Debug, "aoc_2023::day_1"));
+        assert!(filter.enabled(Level::Trace, "aoc_2023::day_5"));
+        assert!(filter.enabled(Level::Trace, "aoc_2023::day_14"));
+        assert!(!filter.enabled(Level::Trace, "aoc_2023::day_15"));
+        assert!(filter.enabled(Level::Debug, "aoc_2023::day_5::tests"));
+
+        let quiet = Filter::from_spec("").unwrap();
+        assert!(quiet.enabled(Level::Warn, "aoc_2023::runner"));
+        assert!(!quiet.enabled(Level::Info, "aoc_2023::runner"));
+
+        assert!(Filter::from_spec("day_5=loud").is_err());
+        assert_eq!(Filter::from_spec("day_5=warn,day_5=DEBUG").map(|x| x.enabled(Level::Debug, "aoc_2023::day_5")), Ok(true));
+    }
+}
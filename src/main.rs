@@ -1,34 +1,312 @@
-#![allow(dead_code)]
-pub mod generic;
-pub mod day_1;
-pub mod day_2;
-pub mod day_3;
-pub mod day_4;
-pub mod day_5;
-pub mod day_6;
-pub mod day_7;
-pub mod day_8;
-pub mod day_9;
-pub mod day_10;
-pub mod day_11;
-pub mod day_12;
-pub mod day_13;
-pub mod day_14;
-pub mod day_15;
-pub mod day_16;
-pub mod day_17;
-
-
-fn main() {
-    println!("Hello, world!");
+use std::path::Path;
+use std::process::ExitCode;
+
+use aoc_2023::answers::{Answers, Check, ANSWERS_FILE};
+use aoc_2023::client::{self, Config, FetchOutcome, SubmitOutcome, CONFIG_FILE};
+use aoc_2023::guesses::{GuessLog, GUESSES_FILENAME};
+use aoc_2023::log::{self, Filter};
+use aoc_2023::runner::{self, ReportFormat, ReportOptions};
+use aoc_2023::scaffold;
+
+const USAGE: &str = "Usage:
+    AOC_2023 run <day> <part> [--input <path>] [--record]
+    AOC_2023 report <day> <part> [--input <path>] [--format csv|json|dot] [--ghosts] [--histogram]
+    AOC_2023 all
+    AOC_2023 fetch <day>
+    AOC_2023 submit <day> <part> [<answer>]
+    AOC_2023 new <day>
+
+Reports default to csv, except day 8 which is only dot. Day 7's csv has a row per hand, and
+--histogram gives the count of each hand type instead, which the json has as well. --ghosts
+colours each ghost's part of day 8's network.
+
+Any command also takes --log <filter>, e.g. --log info,day_5=trace, which overrides AOC_LOG.";
+
+
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Run { day: usize, part: usize, input_filename: Option<String>, record: bool },
+    Report { day: usize, part: usize, input_filename: Option<String>, options: ReportOptions },
+    All,
+    Fetch { day: usize },
+    Submit { day: usize, part: usize, answer: Option<String> },
+    New { day: usize },
+}
+
+impl Command {
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        match args.first().map(|x| x.as_str()) {
+            Some("run") => {
+                let mut positional: Vec<&String> = Vec::new();
+                let mut input_filename: Option<String> = None;
+                let mut record = false;
+                let mut arg_index = 1;
+                while arg_index < args.len() {
+                    if args[arg_index] == "--record" {
+                        record = true;
+                    } else if args[arg_index] == "--input" {
+                        arg_index += 1;
+                        input_filename = Some(args.get(arg_index).ok_or("--input needs a path")?.clone());
+                    } else {
+                        positional.push(&args[arg_index]);
+                    }
+                    arg_index += 1;
+                }
+
+                if positional.len() != 2 {
+                    return Err("run needs a day and a part".to_string());
+                }
+
+                Ok(Self::Run {
+                    day: parse_number(positional[0], "day")?,
+                    part: parse_number(positional[1], "part")?,
+                    input_filename,
+                    record,
+                })
+            },
+            Some("report") => {
+                let mut positional: Vec<&String> = Vec::new();
+                let mut input_filename: Option<String> = None;
+                let mut options = ReportOptions::default();
+                let mut arg_index = 1;
+                while arg_index < args.len() {
+                    if args[arg_index] == "--ghosts" {
+                        options.ghosts = true;
+                    } else if args[arg_index] == "--histogram" {
+                        options.histogram = true;
+                    } else if args[arg_index] == "--input" {
+                        arg_index += 1;
+                        input_filename = Some(args.get(arg_index).ok_or("--input needs a path")?.clone());
+                    } else if args[arg_index] == "--format" {
+                        arg_index += 1;
+                        let name = args.get(arg_index).ok_or("--format needs csv, json or dot")?;
+                        options.format = Some(ReportFormat::from_name(name).ok_or(format!("Unknown format '{}'", name))?);
+                    } else {
+                        positional.push(&args[arg_index]);
+                    }
+                    arg_index += 1;
+                }
+
+                if positional.len() != 2 {
+                    return Err("report needs a day and a part".to_string());
+                }
+
+                Ok(Self::Report {
+                    day: parse_number(positional[0], "day")?,
+                    part: parse_number(positional[1], "part")?,
+                    input_filename,
+                    options,
+                })
+            },
+            Some("all") => Ok(Self::All),
+            Some("fetch") => match args.len() {
+                2 => Ok(Self::Fetch { day: parse_number(&args[1], "day")? }),
+                _ => Err("fetch needs a day".to_string()),
+            },
+            Some("submit") => match args.len() {
+                3 | 4 => Ok(Self::Submit {
+                    day: parse_number(&args[1], "day")?,
+                    part: parse_number(&args[2], "part")?,
+                    answer: args.get(3).cloned(),
+                }),
+                _ => Err("submit needs a day, a part and optionally an answer".to_string()),
+            },
+            Some("new") => match args.len() {
+                2 => Ok(Self::New { day: parse_number(&args[1], "day")? }),
+                _ => Err("new needs a day".to_string()),
+            },
+            Some(other) => Err(format!("Unknown command '{}'", other)),
+            None => Err("No command given".to_string()),
+        }
+    }
+
+    fn execute(self) -> Result<(), String> {
+        match self {
+            Self::Run { day, part, input_filename, record } => {
+                let input_filename = input_filename.unwrap_or(runner::default_input(day));
+                let answer = runner::run(day, part, Some(input_filename.clone()))?;
+                let mut answers = Answers::load(ANSWERS_FILE)?;
+
+                match answers.check(day, part, &input_filename, &answer) {
+                    Check::Fail { expected } => println!("Day {} part {}: {} (FAIL, expected {})", day, part, answer, expected),
+                    check => println!("Day {} part {}: {} ({})", day, part, answer, check),
+                }
+
+                if record {
+                    if let Some(previous) = answers.record(day, part, &input_filename, &answer) {
+                        println!("Replacing previously recorded answer {}", previous);
+                    }
+                    answers.save(ANSWERS_FILE)?;
+                    println!("Recorded answer in {}", ANSWERS_FILE);
+                }
+            },
+            Self::Report { day, part, input_filename, options } => {
+                println!("{}", runner::report(day, part, input_filename, options)?);
+            },
+            Self::All => {
+                let reports = runner::run_all(&Answers::load(ANSWERS_FILE)?);
+                println!("{}", runner::format_table(&reports));
+
+                let failed_days: Vec<usize> = reports.iter().filter(|x| x.is_failure()).map(|x| x.day).collect();
+                if !failed_days.is_empty() {
+                    return Err(format!("Days {:?} did not match their known answers", failed_days));
+                }
+            },
+            Self::Fetch { day } => {
+                let input_filename = runner::default_input(day);
+                match client::fetch_input(&Config::load(CONFIG_FILE)?, day, &input_filename)? {
+                    FetchOutcome::Downloaded => println!("Downloaded day {} input to {}", day, input_filename),
+                    FetchOutcome::Cached => println!("Day {} input is already at {}", day, input_filename),
+                }
+            },
+            Self::Submit { day, part, answer } => {
+                // Without an answer, submit whatever the solver gives for input.txt.
+                let answer = match answer {
+                    Some(answer) => answer,
+                    None => runner::run(day, part, None)?,
+                };
+
+                let guesses_filename = runner::inputs_folder(day) + "/" + GUESSES_FILENAME;
+                let mut guess_log = GuessLog::load(&guesses_filename)?;
+                guess_log.check_guess(part, &answer).map_err(|e| format!("Not submitting: {}", e))?;
+
+                let outcome = client::submit_answer(&Config::load(CONFIG_FILE)?, day, part, &answer)?;
+                guess_log.add(part, &answer, outcome.clone());
+                guess_log.save(&guesses_filename)?;
+
+                if outcome != SubmitOutcome::Correct {
+                    return Err(format!("Day {} part {}: {} was {}", day, part, answer, outcome.name().replace('_', " ")));
+                }
+
+                println!("Day {} part {}: {} is correct", day, part, answer);
+                let mut answers = Answers::load(ANSWERS_FILE)?;
+                answers.record(day, part, &runner::default_input(day), &answer);
+                answers.save(ANSWERS_FILE)?;
+            },
+            Self::New { day } => {
+                for step in scaffold::new_day(Path::new("."), day)? {
+                    println!("{}", step);
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_number(input_string: &str, name: &str) -> Result<usize, String> {
+    input_string.parse::<usize>().map_err(|_| format!("{} '{}' is not a number", name, input_string))
+}
+
+/// Remove `--log <filter>` from the arguments, wherever it appears, and return the filter.
+fn take_log_filter(args: &mut Vec<String>) -> Result<Option<Filter>, String> {
+    let Some(flag_index) = args.iter().position(|x| x == "--log") else {
+        return Ok(None);
+    };
+    if flag_index + 1 >= args.len() {
+        return Err("--log needs a filter".to_string());
+    }
+
+    let spec = args.remove(flag_index + 1);
+    args.remove(flag_index);
+    Filter::from_spec(&spec).map(Some)
+}
+
+fn parse_args(mut args: Vec<String>) -> Result<Command, String> {
+    if let Some(filter) = take_log_filter(&mut args)? {
+        log::set_filter(filter);
+    }
+    Command::from_args(&args)
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args(std::env::args().skip(1).collect()) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("Error: {}", message);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    match command.execute() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("Error: {}", message);
+            ExitCode::FAILURE
+        },
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn to_args(input_string: &str) -> Vec<String> {
+        input_string.split_whitespace().map(|x| x.to_string()).collect()
+    }
 
     #[test]
     fn first_example() {
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_run() {
+        assert_eq!(Command::from_args(&to_args("run 5 2")), Ok(Command::Run { day: 5, part: 2, input_filename: None, record: false }));
+        assert_eq!(
+            Command::from_args(&to_args("run 14 1 --input other.txt")),
+            Ok(Command::Run { day: 14, part: 1, input_filename: Some("other.txt".to_string()), record: false })
+        );
+        assert_eq!(
+            Command::from_args(&to_args("run 14 1 --record")),
+            Ok(Command::Run { day: 14, part: 1, input_filename: None, record: true })
+        );
+        assert!(Command::from_args(&to_args("run 5")).is_err());
+        assert!(Command::from_args(&to_args("run five 1")).is_err());
+        assert!(Command::from_args(&to_args("run 5 1 --input")).is_err());
+        assert!(Command::from_args(&to_args("walk 5 1")).is_err());
+        assert_eq!(
+            Command::from_args(&to_args("report 7 2 --format json")),
+            Ok(Command::Report { day: 7, part: 2, input_filename: None, options: ReportOptions { format: Some(ReportFormat::Json), ..Default::default() } })
+        );
+        assert_eq!(
+            Command::from_args(&to_args("report 7 1 --input other.txt")),
+            Ok(Command::Report { day: 7, part: 1, input_filename: Some("other.txt".to_string()), options: ReportOptions::default() })
+        );
+        assert_eq!(
+            Command::from_args(&to_args("report 8 1 --ghosts")),
+            Ok(Command::Report { day: 8, part: 1, input_filename: None, options: ReportOptions { ghosts: true, ..Default::default() } })
+        );
+        assert_eq!(
+            Command::from_args(&to_args("report 7 2 --histogram")),
+            Ok(Command::Report { day: 7, part: 2, input_filename: None, options: ReportOptions { histogram: true, ..Default::default() } })
+        );
+        assert!(Command::from_args(&to_args("report 7 1 --format xml")).is_err());
+        assert!(Command::from_args(&to_args("report 7")).is_err());
+        assert_eq!(Command::from_args(&to_args("all")), Ok(Command::All));
+        assert_eq!(Command::from_args(&to_args("fetch 18")), Ok(Command::Fetch { day: 18 }));
+        assert!(Command::from_args(&to_args("fetch")).is_err());
+        assert_eq!(Command::from_args(&to_args("submit 5 2")), Ok(Command::Submit { day: 5, part: 2, answer: None }));
+        assert_eq!(
+            Command::from_args(&to_args("submit 5 2 9284340")),
+            Ok(Command::Submit { day: 5, part: 2, answer: Some("9284340".to_string()) })
+        );
+        assert!(Command::from_args(&to_args("submit 5")).is_err());
+        assert_eq!(Command::from_args(&to_args("new 18")), Ok(Command::New { day: 18 }));
+        assert!(Command::from_args(&to_args("new")).is_err());
+    }
+
+    #[test]
+    fn parse_log() {
+        let mut args = to_args("run 5 2 --log day_5=trace --record");
+        assert_eq!(take_log_filter(&mut args), Ok(Some(Filter::from_spec("day_5=trace").unwrap())));
+        assert_eq!(args, to_args("run 5 2 --record"));
+
+        let mut args = to_args("all");
+        assert_eq!(take_log_filter(&mut args), Ok(None));
+        assert!(take_log_filter(&mut to_args("all --log")).is_err());
+        assert!(take_log_filter(&mut to_args("--log day_5=loud all")).is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::answers::{Answers, Check};
+use crate::error::AocError;
+use crate::generic::{self, Solution};
+
+pub const INPUTS_ROOT: &str = "src/inputs";
+
+/// A single entry in the registry of solved days.
+pub struct DayEntry {
+    pub day: usize,
+    pub run_parts: fn(&[String], &[usize]) -> Result<TimedRun, AocError>,
+}
+
+impl DayEntry {
+    const fn new<S: Solution>(day: usize) -> Self {
+        Self { day, run_parts: run_timed::<S> }
+    }
+}
+
+/// The answer to one part and how long it took.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: usize,
+    pub answer: String,
+    pub time: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimedRun {
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+fn run_timed<S: Solution>(input_lines: &[String], parts: &[usize]) -> Result<TimedRun, AocError> {
+    let input_lines = input_lines.to_vec();
+    let parse_start = Instant::now();
+    let parsed = S::parse(input_lines)?;
+    let parse_time = parse_start.elapsed();
+
+    let parts = parts.iter().map(|part| {
+        let part_start = Instant::now();
+        let answer = if *part == 2 { S::part_2(&parsed) } else { S::part_1(&parsed) };
+        PartResult { part: *part, answer: answer.to_string(), time: part_start.elapsed() }
+    }).collect();
+
+    Ok(TimedRun { parse_time, parts })
+}
+
+pub const DAYS: &[DayEntry] = &[
+    DayEntry::new::<crate::day_1::Day1>(1),
+    DayEntry::new::<crate::day_2::Day2>(2),
+    DayEntry::new::<crate::day_3::Day3>(3),
+    DayEntry::new::<crate::day_4::Day4>(4),
+    DayEntry::new::<crate::day_5::Day5>(5),
+    DayEntry::new::<crate::day_6::Day6>(6),
+    DayEntry::new::<crate::day_7::Day7>(7),
+    DayEntry::new::<crate::day_8::Day8>(8),
+    DayEntry::new::<crate::day_9::Day9>(9),
+    DayEntry::new::<crate::day_10::Day10>(10),
+    DayEntry::new::<crate::day_11::Day11>(11),
+    DayEntry::new::<crate::day_12::Day12>(12),
+    DayEntry::new::<crate::day_13::Day13>(13),
+    DayEntry::new::<crate::day_14::Day14>(14),
+    DayEntry::new::<crate::day_15::Day15>(15),
+    DayEntry::new::<crate::day_16::Day16>(16),
+    DayEntry::new::<crate::day_17::Day17>(17),
+];
+
+pub fn get_day(day: usize) -> Option<&'static DayEntry> {
+    DAYS.iter().find(|x| x.day == day)
+}
+
+pub fn inputs_folder(day: usize) -> String {
+    format!("{}/day_{}", INPUTS_ROOT, day)
+}
+
+pub fn default_input(day: usize) -> String {
+    inputs_folder(day) + "/input.txt"
+}
+
+/// Solve one part of one day. Uses the day's `input.txt` unless another file is given.
+pub fn run(day: usize, part: usize, input_filename: Option<String>) -> Result<String, String> {
+    let entry = get_day(day).ok_or(format!("Day {} has not been solved", day))?;
+    let (input_filename, input_lines) = read_input(day, part, input_filename)?;
+
+    let timed_run = (entry.run_parts)(&input_lines, &[part]).map_err(|e| e.in_file(&input_filename).show_line(&input_lines))?;
+    Ok(timed_run.parts[0].answer.clone())
+}
+
+fn read_input(day: usize, part: usize, input_filename: Option<String>) -> Result<(String, Vec<String>), String> {
+    if part != 1 && part != 2 {
+        return Err(format!("Part must be 1 or 2, not {}", part));
+    }
+
+    let input_filename = input_filename.unwrap_or(default_input(day));
+    if !Path::new(&input_filename).is_file() {
+        return Err(format!("Input file {} does not exist", input_filename));
+    }
+
+    let input_lines = generic::read_in_file(&input_filename).map_err(|e| e.to_string())?;
+    Ok((input_filename, input_lines))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+    Dot,
+}
+
+impl ReportFormat {
+    pub fn from_name(input_string: &str) -> Option<Self> {
+        match input_string {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "dot" => Some(Self::Dot),
+            _ => None,
+        }
+    }
+}
+
+/// What to put in a report, and how to write it.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ReportOptions {
+    /// Each day uses the first format it supports if this isn't given.
+    pub format: Option<ReportFormat>,
+    /// Colour in each ghost's part of day 8's network.
+    pub ghosts: bool,
+    /// Only the count of each hand type for day 7, which a CSV of the hands leaves out.
+    pub histogram: bool,
+}
+
+/// A breakdown of how one part of a day got its answer, for the days that have one.
+///
+/// Options for a different day than the one asked for are an error.
+pub fn report(day: usize, part: usize, input_filename: Option<String>, options: ReportOptions) -> Result<String, String> {
+    if options.ghosts && day != 8 {
+        return Err("--ghosts is only for day 8".to_string());
+    }
+    if options.histogram && day != 7 {
+        return Err("--histogram is only for day 7".to_string());
+    }
+    let (input_filename, input_lines) = read_input(day, part, input_filename)?;
+    let show_error = |e: AocError| e.in_file(&input_filename).show_line(&input_lines);
+
+    match day {
+        7 => {
+            let hands = crate::day_7::Day7::parse(input_lines.clone()).map_err(show_error)?;
+            let rules = if part == 2 { crate::day_7::Rules::jokers() } else { crate::day_7::Rules::standard() };
+            let report = crate::day_7::Report::new(&hands, &rules).map_err(show_error)?;
+            match options.format.unwrap_or(ReportFormat::Csv) {
+                ReportFormat::Csv if options.histogram => Ok(report.histogram_to_csv()),
+                ReportFormat::Csv => Ok(report.to_csv()),
+                ReportFormat::Json if options.histogram => Err("The json report always has the histogram".to_string()),
+                ReportFormat::Json => Ok(report.to_json()),
+                ReportFormat::Dot => Err("Day 7 reports can be csv or json".to_string()),
+            }
+        },
+        // The network, which is the same for both parts.
+        8 => {
+            let network = crate::day_8::Day8::parse(input_lines.clone()).map_err(show_error)?;
+            match options.format.unwrap_or(ReportFormat::Dot) {
+                ReportFormat::Dot => Ok(network.to_dot(options.ghosts)),
+                _ => Err("Day 8 reports can only be dot".to_string()),
+            }
+        },
+        _ => Err(format!("Day {} has no report", day)),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DayOutcome {
+    Solved { timed_run: TimedRun, checks: Vec<Check> },
+    MissingInput(String),
+    BadInput(AocError),
+    Panicked(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: usize,
+    pub outcome: DayOutcome,
+}
+
+impl DayReport {
+    pub fn is_failure(&self) -> bool {
+        match &self.outcome {
+            DayOutcome::Solved { checks, .. } => checks.iter().any(|x| matches!(x, Check::Fail { .. })),
+            DayOutcome::MissingInput(_) => false,
+            DayOutcome::BadInput(_) | DayOutcome::Panicked(_) => true,
+        }
+    }
+}
+
+/// Run both parts of every registered day against its `input.txt`.
+pub fn run_all(answers: &Answers) -> Vec<DayReport> {
+    DAYS.iter().map(|entry| {
+        let input_filename = default_input(entry.day);
+        if !Path::new(&input_filename).is_file() {
+            return DayReport { day: entry.day, outcome: DayOutcome::MissingInput(input_filename) };
+        }
+
+        let input_lines = match generic::read_in_file(&input_filename) {
+            Ok(input_lines) => input_lines,
+            Err(error) => return DayReport { day: entry.day, outcome: DayOutcome::BadInput(error) },
+        };
+        let outcome = match panic::catch_unwind(|| (entry.run_parts)(&input_lines, &[1, 2])) {
+            Ok(Ok(timed_run)) => {
+                let checks = timed_run.parts.iter().map(|x| answers.check(entry.day, x.part, &input_filename, &x.answer)).collect();
+                DayOutcome::Solved { timed_run, checks }
+            },
+            Ok(Err(error)) => DayOutcome::BadInput(error.in_file(&input_filename)),
+            Err(panic_payload) => {
+                let message = panic_payload.downcast_ref::<String>().cloned()
+                    .or(panic_payload.downcast_ref::<&str>().map(|x| x.to_string()))
+                    .unwrap_or("unknown panic".to_string());
+                DayOutcome::Panicked(message)
+            },
+        };
+
+        DayReport { day: entry.day, outcome }
+    }).collect()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+/// Lay out the results of `run_all` as a plain text table.
+pub fn format_table(reports: &[DayReport]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(format!("{:>3}  {:>16}  {:>16}  {:>10}  {:>10}  {:>10}  {:>7}  {:>7}",
+        "Day", "Part 1", "Part 2", "Parse", "Part 1", "Part 2", "Check 1", "Check 2"));
+
+    for report in reports {
+        let row = match &report.outcome {
+            DayOutcome::Solved { timed_run, checks } => format!("{:>3}  {:>16}  {:>16}  {:>10}  {:>10}  {:>10}  {:>7}  {:>7}",
+                report.day,
+                timed_run.parts[0].answer,
+                timed_run.parts[1].answer,
+                format_duration(timed_run.parse_time),
+                format_duration(timed_run.parts[0].time),
+                format_duration(timed_run.parts[1].time),
+                checks[0],
+                checks[1]),
+            DayOutcome::MissingInput(input_filename) => format!("{:>3}  no input at {}", report.day, input_filename),
+            DayOutcome::BadInput(error) => format!("{:>3}  ERROR: {}", report.day, error),
+            DayOutcome::Panicked(message) => format!("{:>3}  ERROR: {}", report.day, message),
+        };
+        lines.push(row);
+    }
+
+    lines.join("\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_complete() {
+        for (i, entry) in DAYS.iter().enumerate() {
+            assert_eq!(entry.day, i + 1);
+        }
+        assert!(get_day(17).is_some());
+        assert!(get_day(18).is_none());
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert!(run(18, 1, None).is_err());
+        assert!(run(1, 3, None).is_err());
+        assert!(run(1, 1, Some("src/inputs/day_1/does_not_exist.txt".to_string())).is_err());
+    }
+
+    #[test]
+    fn timed_example() {
+        let input_lines = generic::read_in_file(&(inputs_folder(2) + "/input_example_1.txt")).unwrap();
+        let timed_run = (get_day(2).unwrap().run_parts)(&input_lines, &[1, 2]).unwrap();
+        assert_eq!(timed_run.parts.iter().map(|x| x.answer.as_str()).collect::<Vec<&str>>(), vec!["8", "2286"]);
+    }
+
+    #[test]
+    fn example_input() {
+        let answer = run(2, 1, Some(inputs_folder(2) + "/input_example_1.txt"));
+        println!("Answer = {:?}", answer);
+        assert_eq!(answer, Ok("8".to_string()));
+    }
+
+    #[test]
+    fn bad_input() {
+        let input_filename = std::env::temp_dir().join(format!("aoc_2023_bad_input_{}.txt", std::process::id()));
+        std::fs::write(&input_filename, "32T3K 765\nT55J5 68x\n").unwrap();
+        let input_filename = input_filename.to_string_lossy().to_string();
+
+        let error = run(7, 1, Some(input_filename.clone()));
+        assert_eq!(error, Err(format!("{}:2:7: bid '68x' is not a number\n    T55J5 68x\n          ^", input_filename)));
+        std::fs::remove_file(&input_filename).unwrap();
+    }
+}
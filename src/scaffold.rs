@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::Path;
+
+use crate::runner;
+
+const TEMPLATE: &str = include_str!("day_x.rs");
+const MODULES_FILE: &str = "src/lib.rs";
+const REGISTRY_FILE: &str = "src/runner.rs";
+const EXAMPLE_FILENAME: &str = "input_example_1.txt";
+
+/// Fill in the `day_x.rs` template for one day.
+pub fn day_source(day: usize) -> String {
+    TEMPLATE
+        .replace("\"src/inputs/day_x\"", &format!("\"{}\"", runner::inputs_folder(day)))
+        .replace("DayX", &format!("Day{}", day))
+        .replace("assert_answer(0,", &format!("assert_answer({},", day))
+}
+
+/// Set up everything needed to start on a new day under `root`.
+///
+/// Files that already exist are left alone and modules that are already registered are not added
+/// again, so this is safe to run more than once. Returns a line describing each step.
+pub fn new_day(root: &Path, day: usize) -> Result<Vec<String>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("Day must be between 1 and 25, not {}", day));
+    }
+
+    let mut steps: Vec<String> = Vec::new();
+
+    let inputs_folder = runner::inputs_folder(day);
+    fs::create_dir_all(root.join(&inputs_folder)).map_err(|e| format!("unable to create {}: {}", inputs_folder, e))?;
+    for input_name in ["input.txt", EXAMPLE_FILENAME] {
+        steps.push(create_file(root, &format!("{}/{}", inputs_folder, input_name), "")?);
+    }
+    steps.push(create_file(root, &format!("src/day_{}.rs", day), &day_source(day))?);
+
+    let module_line = format!("pub mod day_{};", day);
+    steps.push(insert_line(root, MODULES_FILE, &module_line, |line| {
+        line.strip_prefix("pub mod day_").and_then(|x| x.strip_suffix(';')).and_then(|x| x.parse::<usize>().ok())
+    }, day)?);
+
+    let entry_line = format!("    DayEntry::new::<crate::day_{}::Day{}>({}),", day, day, day);
+    steps.push(insert_line(root, REGISTRY_FILE, &entry_line, |line| {
+        line.trim().strip_prefix("DayEntry::new::<crate::day_").and_then(|x| x.split_once("::")).and_then(|(x, _)| x.parse::<usize>().ok())
+    }, day)?);
+
+    Ok(steps)
+}
+
+fn create_file(root: &Path, filename: &str, contents: &str) -> Result<String, String> {
+    let path = root.join(filename);
+    if path.exists() {
+        return Ok(format!("{} already exists", filename));
+    }
+
+    fs::write(&path, contents).map_err(|e| format!("unable to write {}: {}", filename, e))?;
+    Ok(format!("Created {}", filename))
+}
+
+/// Add `new_line` to a file among the lines that `get_day` recognises, keeping them in day order.
+fn insert_line(root: &Path, filename: &str, new_line: &str, get_day: impl Fn(&str) -> Option<usize>, day: usize) -> Result<String, String> {
+    let path = root.join(filename);
+    let contents = fs::read_to_string(&path).map_err(|e| format!("unable to read {}: {}", filename, e))?;
+    let mut lines: Vec<&str> = contents.lines().collect();
+
+    if lines.iter().any(|x| get_day(x) == Some(day)) {
+        return Ok(format!("{} already has day {}", filename, day));
+    }
+
+    let day_lines: Vec<(usize, usize)> = lines.iter().enumerate().filter_map(|(i, x)| get_day(x).map(|x| (i, x))).collect();
+    let insert_index = match day_lines.iter().find(|(_, x)| *x > day) {
+        Some((i, _)) => *i,
+        None => day_lines.last().map(|(i, _)| i + 1).ok_or(format!("no existing days found in {}", filename))?,
+    };
+    lines.insert(insert_index, new_line);
+
+    let mut new_contents = lines.join("\n");
+    if contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+    fs::write(&path, new_contents).map_err(|e| format!("unable to write {}: {}", filename, e))?;
+    Ok(format!("Added day {} to {}", day, filename))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn copy_of_src(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc_2023_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        for filename in [MODULES_FILE, REGISTRY_FILE] {
+            fs::copy(filename, root.join(filename)).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn template() {
+        let source = day_source(18);
+        assert!(source.contains("const INPUTS_FOLDER: &str = \"src/inputs/day_18\";"));
+        assert!(source.contains("pub struct Day18;"));
+        assert!(source.contains("generic::solve::<Day18>"));
+        assert!(source.contains("answers::assert_answer(18, 2,"));
+        // A new day shouldn't start out with warnings for the parts not using their input yet.
+        assert!(source.contains("fn part_1(_parsed: &Self::Parsed)") && source.contains("fn part_2(_parsed: &Self::Parsed)"));
+        assert!(!source.contains("day_x") && !source.contains("DayX") && !source.contains("assert_answer(0,"));
+    }
+
+    #[test]
+    fn new_day_twice() {
+        let root = copy_of_src("scaffold");
+        let steps = new_day(&root, 18).unwrap();
+        assert!(steps.iter().all(|x| x.starts_with("Created") || x.starts_with("Added")), "{:?}", steps);
+
+        let day_file = root.join("src/day_18.rs");
+        assert_eq!(fs::read_to_string(&day_file).unwrap(), day_source(18));
+        assert!(root.join("src/inputs/day_18/input.txt").is_file());
+        assert!(root.join("src/inputs/day_18/input_example_1.txt").is_file());
+
+        let modules = fs::read_to_string(root.join(MODULES_FILE)).unwrap();
+        assert!(modules.contains("pub mod day_17;\npub mod day_18;\n"));
+        let registry = fs::read_to_string(root.join(REGISTRY_FILE)).unwrap();
+        assert!(registry.contains("(17),\n    DayEntry::new::<crate::day_18::Day18>(18),\n];"));
+
+        fs::write(&day_file, "// work in progress").unwrap();
+        let steps = new_day(&root, 18).unwrap();
+        assert!(steps.iter().all(|x| x.contains("already")), "{:?}", steps);
+        assert_eq!(fs::read_to_string(&day_file).unwrap(), "// work in progress");
+        assert_eq!(fs::read_to_string(root.join(MODULES_FILE)).unwrap(), modules);
+        assert_eq!(fs::read_to_string(root.join(REGISTRY_FILE)).unwrap(), registry);
+
+        assert!(new_day(&root, 26).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}